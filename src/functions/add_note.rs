@@ -2,44 +2,120 @@ use rusqlite::{Connection, params};
 use aes_gcm::{Aes256Gcm, Key};
 use anyhow::Context;
 use chrono::Utc;
-use crate::functions::utils::encrypt_data;
+use crate::functions::blind_index::index_note;
+use crate::functions::folders::{move_note, read_folder};
+use crate::functions::priorities::Priorities;
+use crate::functions::tags::{add_tags, parse_tag_list};
+use crate::functions::utils::{
+    encrypt_body, encrypt_data, encrypt_with_passphrase, full_text, read_body, read_input, read_passphrase,
+};
+use crate::functions::validation::{limits, print_errors, validate_due, validate_note};
 
 pub fn add_note(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
-    println!("Masukkan catatan (max 255 char): ");
-    let mut note = String::new();
-    std::io::stdin().read_line(&mut note)?;
-    let note = note.trim();
+    let limits = limits();
+    println!("Masukkan judul catatan (max {} karakter): ", limits.title_max);
+    let mut title = String::new();
+    read_input(&mut title)?;
 
-    println!("Masukkan prioritas (1: Tinggi, 2: Sedang, 3: Rendah, kosongkan untuk default Sedang): ");
+    println!(
+        "Masukkan isi catatan (max {} karakter), akhiri dengan baris berisi '.' saja (ketik :editor untuk membuka editor): ",
+        limits.body_max
+    );
+    let body = read_body("")?.unwrap_or_default();
+
+    let (title, body) = match validate_note(&title, &body) {
+        Ok(valid) => valid,
+        Err(errors) => {
+            print_errors(&errors);
+            println!("Catatan tidak disimpan.");
+            return Ok(());
+        }
+    };
+    let title = title.as_str();
+
+    println!("Jadikan catatan rahasia dengan passphrase tambahan? (y/N): ");
+    let mut secret_choice = String::new();
+    read_input(&mut secret_choice)?;
+    let secret = secret_choice.trim().eq_ignore_ascii_case("y");
+    let plain_text = full_text(title, &body);
+    let (title, body) = if secret {
+        match read_passphrase(true)? {
+            Some(passphrase) => (
+                encrypt_with_passphrase(title, &passphrase)?,
+                if body.is_empty() { body } else { encrypt_with_passphrase(&body, &passphrase)? },
+            ),
+            None => {
+                println!("Catatan tidak disimpan.");
+                return Ok(());
+            }
+        }
+    } else {
+        (title.to_string(), body)
+    };
+
+    let priorities = Priorities::load(conn, key)?;
+    println!(
+        "Masukkan prioritas ({}, kosongkan untuk default {}): ",
+        priorities.options(), priorities.default_name()
+    );
     let mut prio_choice = String::new();
-    std::io::stdin().read_line(&mut prio_choice)?;
-    let prio_choice = prio_choice.trim();
-    let prio_choice: i32 = if prio_choice.is_empty() {
-        2 // Default ke Sedang jika input kosong
+    read_input(&mut prio_choice)?;
+    let priority = if prio_choice.trim().is_empty() {
+        priorities.default_name() // Default jika input kosong
     } else {
-        prio_choice.parse().unwrap_or(2) // Default ke Sedang jika parsing gagal
+        priorities.find(&prio_choice).unwrap_or_else(|| {
+            println!("Pilihan tidak valid, menggunakan '{}' sebagai default.", priorities.default_name());
+            priorities.default_name()
+        })
+    };
+
+    println!("Masukkan tag (pisahkan dengan koma, kosongkan jika tidak ada): ");
+    let mut tag_input = String::new();
+    read_input(&mut tag_input)?;
+    let tags = parse_tag_list(&tag_input);
+
+    let folder = match read_folder("Masukkan folder (mis. kerja/klien, kosongkan untuk root): ")? {
+        Ok(folder) => folder,
+        Err(e) => {
+            print_errors(&[e]);
+            println!("Catatan disimpan di root.");
+            String::new()
+        }
     };
 
-    let priority = match prio_choice {
-        1 => "Tinggi",
-        2 => "Sedang",
-        3 => "Rendah",
-        _ => {
-            println!("Pilihan tidak valid, menggunakan 'Sedang' sebagai default.");
-            "Sedang"
+    println!("Masukkan tenggat (YYYY-MM-DD atau YYYY-MM-DD HH:MM, kosongkan jika tidak ada): ");
+    let mut due_input = String::new();
+    read_input(&mut due_input)?;
+    let due_at = if due_input.trim().is_empty() {
+        None
+    } else {
+        match validate_due(&due_input) {
+            Ok(due_at) => Some(due_at.to_rfc3339()),
+            Err(e) => {
+                print_errors(&[e]);
+                println!("Catatan disimpan tanpa tenggat.");
+                None
+            }
         }
     };
 
-    let encrypted_note = encrypt_data(note, key)?;
+    let encrypted_title = encrypt_data(&title, key)?;
+    let encrypted_body = encrypt_body(&body, key)?;
     let encrypted_priority = encrypt_data(priority, key)?;
     let timestamp = Utc::now().to_rfc3339();
     let created_at = timestamp.clone();
     let modified_at = timestamp;
 
     conn.execute(
-        "INSERT INTO notes (note, priority, createdAt, modifiedAt) VALUES (?1, ?2, ?3, ?4)",
-        params![encrypted_note, encrypted_priority, created_at, modified_at],
+        "INSERT INTO notes (note, body, priority, createdAt, modifiedAt, secret, dueAt) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![encrypted_title, encrypted_body, encrypted_priority, created_at, modified_at, secret, due_at],
     ).context("Failed to insert note")?;
+    let id = conn.last_insert_rowid() as i32;
+    if !secret {
+        index_note(conn, key, id, &plain_text)?;
+    }
+    add_tags(conn, key, id, &tags)?;
+    move_note(conn, key, id, &folder)?;
     println!("Catatan berhasil ditambahkan!");
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,78 @@
+use rusqlite::Connection;
+use textwrap::wrap;
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use chrono::{Datelike, Duration, Local, NaiveDate, Utc};
+use crate::functions::checklist::checklist_map;
+use crate::functions::tags::note_tag_map;
+use crate::functions::utils::{NOTE_COLUMNS, Note, format_due, note_from_row};
+use crate::functions::priorities::Priorities;
+
+// Kelompok agenda, urut dari yang paling mendesak
+const GROUPS: [&str; 4] = ["Terlambat", "Hari Ini", "Minggu Ini", "Nanti"];
+
+// Minggu ini dihitung sampai hari Minggu (waktu lokal)
+fn group_index(note: &Note, today: NaiveDate) -> Option<usize> {
+    let due_at = note.due_at?;
+    let due_day = due_at.with_timezone(&Local).date_naive();
+    let end_of_week = today + Duration::days(6 - today.weekday().num_days_from_monday() as i64);
+    Some(if due_at < Utc::now() {
+        0
+    } else if due_day == today {
+        1
+    } else if due_day <= end_of_week {
+        2
+    } else {
+        3
+    })
+}
+
+// Catatan bertenggat dikelompokkan, diurutkan berdasarkan tenggat lalu prioritas
+pub fn show_agenda(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM notes WHERE dueAt IS NOT NULL AND archived = 0 AND deletedAt IS NULL", NOTE_COLUMNS))
+        .context("Failed to prepare statement")?;
+    let notes = stmt.query_and_then([], |row| note_from_row(row, key))
+        .context("Failed to query notes with due dates")?
+        .collect::<anyhow::Result<Vec<Note>>>()?;
+
+    let today = Local::now().date_naive();
+    let mut groups: [Vec<Note>; 4] = Default::default();
+    let mut tag_map = note_tag_map(conn, key)?;
+    let progress = checklist_map(conn)?;
+    let priorities = Priorities::load(conn, key)?;
+    for mut note in notes {
+        note.tags = tag_map.remove(&note.id).unwrap_or_default();
+        note.checklist = progress.get(&note.id).copied().unwrap_or_default();
+        if let Some(index) = group_index(&note, today) {
+            groups[index].push(note);
+        }
+    }
+
+    println!("\nAgenda ({}):", today.format("%Y-%m-%d"));
+    if groups.iter().all(Vec::is_empty) {
+        println!("Belum ada catatan dengan tenggat.");
+        return Ok(());
+    }
+    for (name, notes) in GROUPS.iter().zip(groups.iter_mut()) {
+        if notes.is_empty() {
+            continue;
+        }
+        notes.sort_by_key(|n| (n.due_at, priorities.rank(&n.priority)));
+        println!("\n{} ({}):", name, notes.len());
+        println!("| {:<4} | {:<60} | {:<10} | {:<16} |", "ID", "Judul", "Priority", "Tenggat");
+        println!("|------|--------------------------------------------------------------|------------|------------------|");
+        for note in notes.iter() {
+            let label = note.list_label();
+            let due = note.due_at.map(format_due).unwrap_or_default();
+            for (i, line) in wrap(&label, 60).iter().enumerate() {
+                if i == 0 {
+                    println!("| {:<4} | {:<60} | {} | {:<16} |", note.id, line, priorities.label(&note.priority, 10), due);
+                } else {
+                    println!("| {:<4} | {:<60} | {:<10} | {:<16} |", "", line, "", "");
+                }
+            }
+            println!("|------|--------------------------------------------------------------|------------|------------------|");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use rusqlite::{Connection, params};
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use crate::functions::query_filter::parse_filter;
+use crate::functions::show_all_notes::print_all_notes;
+use crate::functions::utils::read_input;
+
+// Catatan arsip tidak bisa disematkan, sama seperti set_archived yang melepas sematan saat mengarsipkan
+pub fn set_pinned(conn: &Connection, id: i32, pinned: bool) -> anyhow::Result<()> {
+    let changed = conn.execute(
+        "UPDATE notes SET pinned = ?1 WHERE id = ?2 AND deletedAt IS NULL AND (NOT ?1 OR archived = 0)",
+        params![pinned, id],
+    ).context("Failed to pin note")?;
+    if changed == 0 && pinned {
+        println!("Catatan dengan ID {} tidak bisa disematkan karena sedang diarsipkan.", id);
+    } else if changed == 0 {
+        println!("Catatan dengan ID {} tidak ditemukan!", id);
+    } else if pinned {
+        println!("Catatan dengan ID {} disematkan.", id);
+    } else {
+        println!("Sematan catatan dengan ID {} dilepas.", id);
+    }
+    Ok(())
+}
+
+// Catatan arsip tidak disematkan lagi agar tidak muncul di atas setelah dipulihkan tanpa sengaja
+pub fn set_archived(conn: &Connection, id: i32, archived: bool) -> anyhow::Result<bool> {
+    let changed = conn.execute(
+        "UPDATE notes SET archived = ?1, pinned = CASE WHEN ?1 THEN 0 ELSE pinned END WHERE id = ?2 AND archived != ?1 AND deletedAt IS NULL",
+        params![archived, id],
+    ).context("Failed to archive note")?;
+    Ok(changed > 0)
+}
+
+pub fn archive_menu(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM notes WHERE archived = 1 AND deletedAt IS NULL", [], |row| row.get(0))
+        .context("Failed to count archived notes")?;
+    println!("\nArsip Catatan ({} catatan):", count);
+    if count == 0 {
+        println!("Arsip kosong.");
+        return Ok(());
+    }
+    let filter = parse_filter("is:archived").map_err(anyhow::Error::msg)?;
+    print_all_notes(conn, key, Some(&filter))?;
+
+    println!("Masukkan ID catatan yang akan dipulihkan (kosongkan untuk kembali): ");
+    let mut id = String::new();
+    read_input(&mut id)?;
+    if id.trim().is_empty() {
+        return Ok(());
+    }
+    let id: i32 = id.trim().parse().unwrap_or(0);
+    if set_archived(conn, id, false)? {
+        println!("Catatan dengan ID {} dipulihkan dari arsip.", id);
+    } else {
+        println!("Catatan arsip dengan ID {} tidak ditemukan!", id);
+    }
+    Ok(())
+}
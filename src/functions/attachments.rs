@@ -0,0 +1,527 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce
+};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use crate::functions::utils::{decrypt_data, derive_mac, encrypt_data, read_input};
+
+// Ukuran potongan file; hanya satu potongan yang ada di memori saat melampirkan atau mengekstrak
+const CHUNK_SIZE: usize = 64 * 1024;
+const DEFAULT_MAX_MB: u64 = 50;
+
+pub struct Attachment {
+    pub id: i64,
+    pub name: String,
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+fn max_size() -> u64 {
+    env::var("ATTACHMENT_MAX_MB")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_MAX_MB)
+        * 1024 * 1024
+}
+
+// ID lampiran dan nomor urut ikut diautentikasi agar potongan tidak bisa ditukar atau dipindah
+fn chunk_aad(attachment_id: i64, seq: i64) -> Vec<u8> {
+    format!("notes_app_rust attachment {}:{}", attachment_id, seq).into_bytes()
+}
+
+fn encrypt_chunk(data: &[u8], attachment_id: i64, seq: i64, key: &Key<Aes256Gcm>) -> anyhow::Result<Vec<u8>> {
+    let cipher = Aes256Gcm::new(key);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let aad = chunk_aad(attachment_id, seq);
+    let ciphertext = cipher.encrypt(&nonce, Payload { msg: data, aad: &aad })
+        .map_err(|e| anyhow::anyhow!("Encryption failed: {:?}", e))?;
+    let mut encrypted = nonce.to_vec();
+    encrypted.extend_from_slice(&ciphertext);
+    Ok(encrypted)
+}
+
+fn decrypt_chunk(encrypted: &[u8], attachment_id: i64, seq: i64, key: &Key<Aes256Gcm>) -> anyhow::Result<Vec<u8>> {
+    if encrypted.len() < 12 {
+        anyhow::bail!("Attachment chunk {} is too short", seq);
+    }
+    let cipher = Aes256Gcm::new(key);
+    let nonce = Nonce::from_slice(&encrypted[0..12]);
+    let aad = chunk_aad(attachment_id, seq);
+    cipher.decrypt(nonce, Payload { msg: &encrypted[12..], aad: &aad })
+        .map_err(|e| anyhow::anyhow!("Decryption failed: {:?}", e))
+}
+
+pub fn load_attachments(conn: &Connection, key: &Key<Aes256Gcm>, note_id: i32) -> anyhow::Result<Vec<Attachment>> {
+    let mut stmt = conn.prepare("SELECT id, name, size, createdAt FROM attachments WHERE note_id = ?1 ORDER BY id ASC")
+        .context("Failed to prepare statement")?;
+    let rows = stmt.query_and_then(params![note_id], |row| -> anyhow::Result<Attachment> {
+        let encrypted_name: Vec<u8> = row.get(1).context("Failed to get attachment name from row")?;
+        let created_at: String = row.get(3).context("Failed to get attachment createdAt from row")?;
+        Ok(Attachment {
+            id: row.get(0).context("Failed to get attachment id from row")?,
+            name: decrypt_data(&encrypted_name, key).context("Failed to decrypt attachment name")?,
+            size: row.get::<_, i64>(2).context("Failed to get attachment size from row")? as u64,
+            created_at: DateTime::parse_from_rfc3339(&created_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .context("Failed to parse attachment createdAt")?,
+        })
+    }).context("Failed to query attachments")?;
+    rows.collect()
+}
+
+// File dibaca dan dienkripsi per potongan di dalam satu transaksi
+fn attach_file(conn: &Connection, key: &Key<Aes256Gcm>, note_id: i32, path: &Path) -> anyhow::Result<Attachment> {
+    let file = File::open(path).context("Failed to open file")?;
+    let size = file.metadata().context("Failed to read file metadata")?.len();
+    if size > max_size() {
+        anyhow::bail!("File terlalu besar ({} byte, maksimal {} byte)", size, max_size());
+    }
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("lampiran").to_string();
+    let created_at = Utc::now();
+
+    let tx = conn.unchecked_transaction().context("Failed to start transaction")?;
+    tx.execute(
+        "INSERT INTO attachments (note_id, name, size, chunks, createdAt) VALUES (?1, ?2, 0, 0, ?3)",
+        params![note_id, encrypt_data(&name, key)?, created_at.to_rfc3339()],
+    ).context("Failed to insert attachment")?;
+    let attachment_id = tx.last_insert_rowid();
+
+    let mut reader = BufReader::new(file);
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let (mut seq, mut written) = (0i64, 0u64);
+    loop {
+        // Isi buffer sampai penuh agar setiap potongan (kecuali terakhir) berukuran sama
+        let mut filled = 0;
+        while filled < CHUNK_SIZE {
+            let read = reader.read(&mut buffer[filled..]).context("Failed to read file")?;
+            if read == 0 {
+                break;
+            }
+            filled += read;
+        }
+        if filled == 0 {
+            break;
+        }
+        tx.execute(
+            "INSERT INTO attachment_chunks (attachment_id, seq, data) VALUES (?1, ?2, ?3)",
+            params![attachment_id, seq, encrypt_chunk(&buffer[..filled], attachment_id, seq, key)?],
+        ).context("Failed to insert attachment chunk")?;
+        seq += 1;
+        written += filled as u64;
+    }
+    tx.execute(
+        "UPDATE attachments SET size = ?1, chunks = ?2 WHERE id = ?3",
+        params![written as i64, seq, attachment_id],
+    ).context("Failed to update attachment")?;
+    tx.commit().context("Failed to commit transaction")?;
+    Ok(Attachment { id: attachment_id, name, size: written, created_at })
+}
+
+// Potongan didekripsi satu per satu langsung ke file tujuan
+fn extract_attachment(conn: &Connection, key: &Key<Aes256Gcm>, attachment: &Attachment, dest: &Path) -> anyhow::Result<()> {
+    let chunks: i64 = conn.query_row(
+        "SELECT chunks FROM attachments WHERE id = ?1",
+        params![attachment.id],
+        |row| row.get(0),
+    ).context("Failed to read attachment")?;
+    let mut stmt = conn.prepare("SELECT seq, data FROM attachment_chunks WHERE attachment_id = ?1 ORDER BY seq ASC")
+        .context("Failed to prepare statement")?;
+    let mut rows = stmt.query(params![attachment.id]).context("Failed to query attachment chunks")?;
+
+    let mut tmp = dest.as_os_str().to_owned();
+    tmp.push(".part");
+    let tmp = PathBuf::from(tmp);
+    let mut writer = BufWriter::new(File::create(&tmp).context("Failed to create file")?);
+    let (mut expected, mut written) = (0i64, 0u64);
+    let result = (|| -> anyhow::Result<()> {
+        while let Some(row) = rows.next().context("Failed to read attachment chunk")? {
+            let seq: i64 = row.get(0)?;
+            if seq != expected {
+                anyhow::bail!("Lampiran rusak: potongan {} hilang", expected);
+            }
+            let data = decrypt_chunk(row.get_ref(1)?.as_blob()?, attachment.id, seq, key)?;
+            writer.write_all(&data).context("Failed to write file")?;
+            written += data.len() as u64;
+            expected += 1;
+        }
+        if expected != chunks || written != attachment.size {
+            anyhow::bail!("Lampiran rusak: ukuran tidak sesuai");
+        }
+        writer.flush().context("Failed to write file")?;
+        Ok(())
+    })();
+    // File sebagian tidak ditinggalkan jika dekripsi gagal
+    match result {
+        Ok(()) => fs::rename(&tmp, dest).context("Failed to move extracted file"),
+        Err(e) => {
+            let _ = fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+// Dipanggil saat catatan dihapus permanen
+pub fn remove_note_attachments(conn: &Connection, note_id: i32) -> anyhow::Result<()> {
+    conn.execute(
+        "DELETE FROM attachment_chunks WHERE attachment_id IN (SELECT id FROM attachments WHERE note_id = ?1)",
+        params![note_id],
+    ).context("Failed to delete attachment chunks")?;
+    conn.execute("DELETE FROM attachments WHERE note_id = ?1", params![note_id])
+        .context("Failed to delete attachments")?;
+    Ok(())
+}
+
+fn remove_attachment(conn: &Connection, attachment_id: i64) -> anyhow::Result<()> {
+    conn.execute("DELETE FROM attachment_chunks WHERE attachment_id = ?1", params![attachment_id])
+        .context("Failed to delete attachment chunks")?;
+    conn.execute("DELETE FROM attachments WHERE id = ?1", params![attachment_id])
+        .context("Failed to delete attachment")?;
+    Ok(())
+}
+
+// File lampiran yang diekspor di samping CSV, misalnya "notes_export.csv.attachments"
+pub fn attachments_path(csv_path: &str) -> String {
+    format!("{}.attachments", csv_path)
+}
+
+// Kunci terpisah dari tanda tangan CSV, agar tanda tangan satu format tidak bisa dipakai untuk format lain
+fn attachment_export_mac(key: &Key<Aes256Gcm>) -> anyhow::Result<Hmac<Sha256>> {
+    derive_mac(key, "notes_app_rust attachment export")
+}
+
+// Semua byte yang ditulis atau dibaca ikut dihitung ke HMAC ekspor, jadi file besar tidak perlu dimuat sekaligus
+struct Signed<T> {
+    inner: T,
+    mac: Hmac<Sha256>,
+}
+
+impl<W: Write> Write for Signed<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.mac.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R: Read> Read for Signed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.mac.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+// Lampiran diekspor apa adanya (nama dan potongan tetap terenkripsi dengan kunci database),
+// satu baris per lampiran diikuti satu baris per potongan. Baris pertama memuat tanda tangan CSV
+// pasangannya agar file lampiran dari ekspor lain tidak bisa dipakai.
+pub fn export_attachments(
+    conn: &Connection,
+    key: &Key<Aes256Gcm>,
+    csv_path: &str,
+    csv_signature: &str,
+) -> anyhow::Result<usize> {
+    let path = attachments_path(csv_path);
+    let file = BufWriter::new(File::create(&path).context("Failed to create attachments file")?);
+    let mut wtr = WriterBuilder::new().flexible(true).from_writer(Signed { inner: file, mac: attachment_export_mac(key)? });
+    wtr.write_record(["export", csv_signature]).context("Failed to write attachments file")?;
+
+    let mut stmt = conn.prepare("SELECT id, note_id, name, size, chunks, createdAt FROM attachments ORDER BY id ASC")
+        .context("Failed to prepare statement")?;
+    let attachments = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?, row.get::<_, i32>(1)?, row.get::<_, Vec<u8>>(2)?,
+            row.get::<_, i64>(3)?, row.get::<_, i64>(4)?, row.get::<_, String>(5)?,
+        ))
+    }).context("Failed to query attachments for export")?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut chunk_stmt = conn.prepare("SELECT seq, data FROM attachment_chunks WHERE attachment_id = ?1 ORDER BY seq ASC")
+        .context("Failed to prepare statement")?;
+    for (id, note_id, name, size, chunks, created_at) in &attachments {
+        wtr.write_record([
+            "attachment", &id.to_string(), &note_id.to_string(), &hex::encode(name),
+            &size.to_string(), &chunks.to_string(), created_at,
+        ]).context("Failed to write attachments file")?;
+        let mut rows = chunk_stmt.query(params![id]).context("Failed to query attachment chunks")?;
+        while let Some(row) = rows.next().context("Failed to read attachment chunk")? {
+            let seq: i64 = row.get(0)?;
+            let data = hex::encode(row.get_ref(1)?.as_blob()?);
+            wtr.write_record(["chunk", &id.to_string(), &seq.to_string(), &data])
+                .context("Failed to write attachments file")?;
+        }
+    }
+
+    let Signed { mut inner, mac } = wtr.into_inner()
+        .map_err(|e| anyhow::anyhow!("Failed to flush attachments file: {}", e.error()))?;
+    inner.flush().context("Failed to write attachments file")?;
+    fs::write(format!("{}.sig", path), hex::encode(mac.finalize().into_bytes()))
+        .context("Failed to write attachments signature")?;
+    Ok(attachments.len())
+}
+
+enum ExportRow {
+    Attachment { id: i64, note_id: i32, name: Vec<u8>, size: i64, chunks: i64, created_at: String },
+    Chunk { attachment_id: i64, seq: i64, data: Vec<u8> },
+}
+
+fn parse_row(record: &StringRecord) -> anyhow::Result<ExportRow> {
+    let field = |i: usize| record.get(i).unwrap_or_default();
+    match field(0) {
+        "attachment" => Ok(ExportRow::Attachment {
+            id: field(1).parse().context("Invalid attachment id")?,
+            note_id: field(2).parse().context("Invalid attachment note id")?,
+            name: hex::decode(field(3)).context("Invalid attachment name")?,
+            size: field(4).parse().context("Invalid attachment size")?,
+            chunks: field(5).parse().context("Invalid attachment chunk count")?,
+            created_at: field(6).to_string(),
+        }),
+        "chunk" => Ok(ExportRow::Chunk {
+            attachment_id: field(1).parse().context("Invalid chunk attachment id")?,
+            seq: field(2).parse().context("Invalid chunk number")?,
+            data: hex::decode(field(3)).context("Invalid chunk data")?,
+        }),
+        kind => anyhow::bail!("Jenis baris '{}' tidak dikenal", kind),
+    }
+}
+
+// Membaca file lampiran ekspor baris demi baris; tanda tangannya baru bisa diperiksa setelah baris terakhir,
+// jadi pemanggil yang menulis ke database harus melakukannya di dalam transaksi
+fn read_export(
+    csv_path: &str,
+    key: &Key<Aes256Gcm>,
+    csv_signature: &str,
+    mut on_row: impl FnMut(ExportRow) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let path = attachments_path(csv_path);
+    let file = BufReader::new(File::open(&path).context("Failed to open attachments file")?);
+    let mut rdr = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(Signed { inner: file, mac: attachment_export_mac(key)? });
+    let mut record = StringRecord::new();
+    if !rdr.read_record(&mut record).context("Failed to read attachments file")?
+        || record.get(0) != Some("export")
+        || record.get(1) != Some(csv_signature)
+    {
+        anyhow::bail!("File lampiran bukan pasangan file CSV ini");
+    }
+    let mut line = 1;
+    while rdr.read_record(&mut record).context("Failed to read attachments file")? {
+        line += 1;
+        on_row(parse_row(&record).with_context(|| format!("Baris {} file lampiran", line))?)?;
+    }
+
+    let Signed { mac, .. } = rdr.into_inner();
+    let signature = fs::read_to_string(format!("{}.sig", path)).context("Tanda tangan file lampiran tidak ditemukan")?;
+    let tag = hex::decode(signature.trim()).unwrap_or_default();
+    if mac.verify_slice(&tag).is_err() {
+        anyhow::bail!("Tanda tangan file lampiran tidak cocok, file telah diubah atau berasal dari database lain");
+    }
+    Ok(())
+}
+
+// Pemeriksaan sebelum import: tanda tangan, pasangan CSV dan kelengkapan potongan; mengembalikan jumlah lampiran
+pub fn verify_attachment_export(csv_path: &str, key: &Key<Aes256Gcm>, csv_signature: &str) -> anyhow::Result<usize> {
+    // Jumlah potongan yang diharapkan dan nomor potongan berikutnya per lampiran
+    let mut expected: HashMap<i64, (i64, i64)> = HashMap::new();
+    read_export(csv_path, key, csv_signature, |row| {
+        match row {
+            ExportRow::Attachment { id, chunks, .. } => {
+                if expected.insert(id, (chunks, 0)).is_some() {
+                    anyhow::bail!("Lampiran {} muncul lebih dari sekali", id);
+                }
+            }
+            ExportRow::Chunk { attachment_id, seq, .. } => {
+                let Some((_, next)) = expected.get_mut(&attachment_id) else {
+                    anyhow::bail!("Potongan untuk lampiran {} yang tidak ada", attachment_id);
+                };
+                if seq != *next {
+                    anyhow::bail!("Potongan {} lampiran {} tidak berurutan", seq, attachment_id);
+                }
+                *next += 1;
+            }
+        }
+        Ok(())
+    })?;
+    if let Some((id, _)) = expected.iter().find(|(_, (chunks, next))| chunks != next) {
+        anyhow::bail!("Potongan lampiran {} tidak lengkap", id);
+    }
+    Ok(expected.len())
+}
+
+// Mengembalikan lampiran ke catatan hasil import (ID lama -> ID baru); lampiran dari catatan yang
+// tidak ikut diimpor dilewati. Mengembalikan jumlah lampiran yang dipulihkan dan yang dilewati.
+pub fn import_attachments(
+    conn: &Connection,
+    key: &Key<Aes256Gcm>,
+    csv_path: &str,
+    csv_signature: &str,
+    note_ids: &HashMap<i32, i32>,
+) -> anyhow::Result<(usize, usize)> {
+    let mut new_ids: HashMap<i64, i64> = HashMap::new();
+    let (mut restored, mut skipped) = (0, 0);
+    read_export(csv_path, key, csv_signature, |row| {
+        match row {
+            ExportRow::Attachment { id, note_id, name, size, chunks, created_at } => {
+                let Some(note_id) = note_ids.get(&note_id) else {
+                    skipped += 1;
+                    return Ok(());
+                };
+                conn.execute(
+                    "INSERT INTO attachments (note_id, name, size, chunks, createdAt) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![note_id, name, size, chunks, created_at],
+                ).context("Failed to insert attachment during import")?;
+                new_ids.insert(id, conn.last_insert_rowid());
+                restored += 1;
+            }
+            ExportRow::Chunk { attachment_id, seq, data } => {
+                let Some(new_id) = new_ids.get(&attachment_id).copied() else {
+                    return Ok(());
+                };
+                // ID lampiran termasuk dalam data autentikasi, jadi potongan dienkripsi ulang untuk ID barunya
+                let plain = decrypt_chunk(&data, attachment_id, seq, key)?;
+                conn.execute(
+                    "INSERT INTO attachment_chunks (attachment_id, seq, data) VALUES (?1, ?2, ?3)",
+                    params![new_id, seq, encrypt_chunk(&plain, new_id, seq, key)?],
+                ).context("Failed to insert attachment chunk during import")?;
+            }
+        }
+        Ok(())
+    })?;
+    Ok((restored, skipped))
+}
+
+pub fn format_size(size: u64) -> String {
+    match size {
+        s if s >= 1024 * 1024 => format!("{:.1} MB", s as f64 / (1024.0 * 1024.0)),
+        s if s >= 1024 => format!("{:.1} KB", s as f64 / 1024.0),
+        s => format!("{} B", s),
+    }
+}
+
+fn print_attachments(attachments: &[Attachment]) {
+    println!("| {:<4} | {:<40} | {:<10} | {:<19} |", "No", "Nama File", "Ukuran", "Dilampirkan");
+    println!("|------|------------------------------------------|------------|---------------------|");
+    for (number, attachment) in attachments.iter().enumerate() {
+        let name: String = attachment.name.chars().take(40).collect();
+        println!(
+            "| {:<4} | {:<40} | {:<10} | {:<19} |",
+            number + 1, name, format_size(attachment.size), attachment.created_at.format("%Y-%m-%d %H:%M:%S").to_string()
+        );
+    }
+    if attachments.is_empty() {
+        println!("Belum ada lampiran.");
+    }
+}
+
+fn read_attachment<'a>(attachments: &'a [Attachment], prompt: &str) -> anyhow::Result<Option<&'a Attachment>> {
+    println!("{}", prompt);
+    let mut input = String::new();
+    read_input(&mut input)?;
+    let attachment = input.trim().parse::<usize>().ok().and_then(|n| attachments.get(n.wrapping_sub(1)));
+    if attachment.is_none() {
+        println!("Nomor lampiran tidak valid!");
+    }
+    Ok(attachment)
+}
+
+pub fn attachments_menu(conn: &Connection, key: &Key<Aes256Gcm>, note_id: i32) -> anyhow::Result<()> {
+    let secret: Option<bool> = conn.query_row(
+        "SELECT secret FROM notes WHERE id = ?1 AND deletedAt IS NULL",
+        params![note_id],
+        |row| row.get(0),
+    ).optional().context("Failed to read note")?;
+    match secret {
+        None => {
+            println!("Catatan dengan ID {} tidak ditemukan!", note_id);
+            return Ok(());
+        }
+        // Lampiran hanya dienkripsi dengan kunci database, belum dengan passphrase catatan
+        Some(true) => {
+            println!("Lampiran belum didukung untuk catatan rahasia.");
+            return Ok(());
+        }
+        Some(false) => {}
+    }
+
+    let attachments = load_attachments(conn, key, note_id)?;
+    println!("\nLampiran Catatan {}:", note_id);
+    print_attachments(&attachments);
+    println!("\nMenu Lampiran:");
+    println!("1. Lampirkan File");
+    println!("2. Ekstrak Lampiran");
+    println!("3. Hapus Lampiran");
+    println!("4. Kembali");
+    println!("Pilih opsi (1-4): ");
+
+    let mut choice = String::new();
+    read_input(&mut choice)?;
+    let choice: i32 = choice.trim().parse().unwrap_or(0);
+
+    match choice {
+        1 => {
+            println!("Masukkan path file yang akan dilampirkan: ");
+            let mut path = String::new();
+            read_input(&mut path)?;
+            match attach_file(conn, key, note_id, Path::new(path.trim())) {
+                Ok(attachment) => println!(
+                    "File '{}' ({}) berhasil dilampirkan!", attachment.name, format_size(attachment.size)
+                ),
+                Err(e) => println!("Gagal melampirkan file: {:#}", e),
+            }
+        }
+        2 => {
+            let Some(attachment) = read_attachment(&attachments, "Nomor lampiran yang akan diekstrak: ")? else {
+                return Ok(());
+            };
+            println!("Simpan ke (kosongkan untuk '{}'): ", attachment.name);
+            let mut dest = String::new();
+            read_input(&mut dest)?;
+            let dest = if dest.trim().is_empty() { attachment.name.clone() } else { dest.trim().to_string() };
+            let dest = Path::new(&dest);
+            if dest.exists() {
+                println!("File '{}' sudah ada. Timpa? (y/N): ", dest.display());
+                let mut confirm = String::new();
+                read_input(&mut confirm)?;
+                if !confirm.trim().eq_ignore_ascii_case("y") {
+                    println!("Lampiran tidak diekstrak.");
+                    return Ok(());
+                }
+            }
+            match extract_attachment(conn, key, attachment, dest) {
+                Ok(()) => println!("Lampiran disimpan ke '{}'.", dest.display()),
+                Err(e) => println!("Gagal mengekstrak lampiran: {:#}", e),
+            }
+        }
+        3 => {
+            let Some(attachment) = read_attachment(&attachments, "Nomor lampiran yang akan dihapus: ")? else {
+                return Ok(());
+            };
+            println!("Hapus lampiran '{}'? (y/N): ", attachment.name);
+            let mut confirm = String::new();
+            read_input(&mut confirm)?;
+            if confirm.trim().eq_ignore_ascii_case("y") {
+                remove_attachment(conn, attachment.id)?;
+                println!("Lampiran '{}' dihapus.", attachment.name);
+            } else {
+                println!("Lampiran tidak dihapus.");
+            }
+        }
+        4 => println!("Kembali."),
+        _ => println!("Pilihan tidak valid!"),
+    }
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use rusqlite::{Connection, OptionalExtension, params, params_from_iter};
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::BTreeSet;
+use std::env;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+use crate::functions::stemmer::{language, stem};
+use crate::functions::utils::{NOTE_COLUMNS, derive_mac, note_from_row};
+
+// Versi format token; naikkan jika cara tokenisasi berubah agar indeks dibangun ulang
+const INDEX_VERSION: &str = "3";
+
+// SEARCH_STRIP_ACCENTS=false mematikan penghapusan aksen (default aktif)
+fn strip_accents() -> bool {
+    env::var("SEARCH_STRIP_ACCENTS")
+        .map(|v| !matches!(v.trim().to_lowercase().as_str(), "false" | "0" | "no" | "tidak"))
+        .unwrap_or(true)
+}
+
+// Pengaturan normalisasi ikut menentukan token, jadi dicatat bersama versi indeks
+fn index_version() -> String {
+    format!(
+        "{}{}-stem-{}",
+        INDEX_VERSION,
+        if strip_accents() { "-noaccent" } else { "" },
+        language().code()
+    )
+}
+
+// NFKC, case folding dan (opsional) penghapusan aksen agar "Café" cocok dengan "cafe"
+pub fn normalize(text: &str) -> String {
+    let folded = text.nfkc().collect::<String>()
+        .to_lowercase()
+        .replace('ß', "ss")
+        .replace('ς', "σ");
+    if strip_accents() {
+        folded.nfd().filter(|c| !is_combining_mark(*c)).nfc().collect()
+    } else {
+        folded
+    }
+}
+
+// Tanda diakritik terpisah (bentuk NFD) tetap dianggap bagian dari kata
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || is_combining_mark(c)
+}
+
+// Posisi byte setiap kata di teks asli beserta bentuk normalnya, dipakai untuk penyorotan
+pub fn word_spans(text: &str) -> Vec<(usize, usize, String)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (is_word_char(c), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i, normalize(&text[s..i])));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len(), normalize(&text[s..])));
+    }
+    spans
+}
+
+// Memecah teks menjadi kata-kata yang sudah dinormalisasi, urutan dipertahankan
+pub fn words(text: &str) -> Vec<String> {
+    word_spans(text).into_iter().map(|(_, _, word)| word).collect()
+}
+
+// Kata-kata unik dari teks, dipakai sebagai isi indeks
+pub fn tokenize(text: &str) -> BTreeSet<String> {
+    words(text).into_iter().collect()
+}
+
+// Bentuk dasar kata-kata unik, disimpan di indeks terpisah dari kata aslinya
+pub fn stems(text: &str) -> BTreeSet<String> {
+    words(text).iter().map(|word| stem(word)).collect()
+}
+
+// Token = HMAC kata dengan kunci turunan, sehingga kata asli tidak terlihat di file database
+fn index_mac(key: &Key<Aes256Gcm>) -> anyhow::Result<Hmac<Sha256>> {
+    derive_mac(key, "notes_app_rust blind index")
+}
+
+fn word_tokens(text: &str, key: &Key<Aes256Gcm>) -> anyhow::Result<Vec<Vec<u8>>> {
+    let mac = index_mac(key)?;
+    Ok(tokenize(text)
+        .into_iter()
+        .map(|word| {
+            let mut mac = mac.clone();
+            mac.update(word.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        })
+        .collect())
+}
+
+// Token bentuk dasar diberi awalan agar tidak tertukar dengan token kata yang sama persis
+fn stem_tokens(text: &str, key: &Key<Aes256Gcm>) -> anyhow::Result<Vec<Vec<u8>>> {
+    let mac = index_mac(key)?;
+    Ok(stems(text)
+        .into_iter()
+        .map(|stem| {
+            let mut mac = mac.clone();
+            mac.update(b"stem:");
+            mac.update(stem.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        })
+        .collect())
+}
+
+// Dipanggil setiap kali isi catatan ditulis
+pub fn index_note(conn: &Connection, key: &Key<Aes256Gcm>, note_id: i32, text: &str) -> anyhow::Result<()> {
+    remove_note(conn, note_id)?;
+    for token in word_tokens(text, key)?.into_iter().chain(stem_tokens(text, key)?) {
+        conn.execute(
+            "INSERT INTO note_tokens (note_id, token) VALUES (?1, ?2)",
+            params![note_id, token],
+        ).context("Failed to insert search token")?;
+    }
+    Ok(())
+}
+
+pub fn remove_note(conn: &Connection, note_id: i32) -> anyhow::Result<()> {
+    conn.execute("DELETE FROM note_tokens WHERE note_id = ?1", params![note_id])
+        .context("Failed to delete search tokens")?;
+    Ok(())
+}
+
+// Mengembalikan ID catatan yang memuat semua kata (atau bentuk dasarnya) pada kata kunci
+pub fn lookup(conn: &Connection, key: &Key<Aes256Gcm>, keyword: &str, stemming: bool) -> anyhow::Result<Vec<i32>> {
+    let tokens = if stemming { stem_tokens(keyword, key)? } else { word_tokens(keyword, key)? };
+    if tokens.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = vec!["?"; tokens.len()].join(", ");
+    let query = format!(
+        "SELECT note_id FROM note_tokens WHERE token IN ({}) GROUP BY note_id HAVING COUNT(DISTINCT token) = {} ORDER BY note_id",
+        placeholders,
+        tokens.len()
+    );
+    let mut stmt = conn.prepare(&query).context("Failed to prepare token lookup")?;
+    let ids = stmt.query_map(params_from_iter(tokens), |row| row.get(0))
+        .context("Failed to query search tokens")?
+        .collect::<Result<Vec<i32>, _>>()?;
+    Ok(ids)
+}
+
+// Membangun ulang seluruh indeks, misalnya setelah import atau untuk database lama
+pub fn rebuild_index(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    conn.execute("DELETE FROM note_tokens", []).context("Failed to clear search tokens")?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM notes WHERE secret = 0", NOTE_COLUMNS))
+        .context("Failed to prepare statement")?;
+    let notes = stmt.query_and_then([], |row| note_from_row(row, key))
+        .context("Failed to query notes for indexing")?;
+    for note in notes {
+        let note = note?;
+        index_note(conn, key, note.id, &note.text())?;
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO metadata (key, value) VALUES ('blind_index_version', ?1)",
+        params![index_version()],
+    ).context("Failed to store index version")?;
+    Ok(())
+}
+
+pub fn ensure_index(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    let version: Option<String> = conn.query_row(
+        "SELECT value FROM metadata WHERE key = 'blind_index_version'",
+        [],
+        |row| row.get(0),
+    ).optional().context("Failed to read index version")?;
+    if version != Some(index_version()) {
+        rebuild_index(conn, key)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Mengandalkan SEARCH_STRIP_ACCENTS tidak diatur, jadi aksen dihapus seperti default aplikasi
+    #[test]
+    fn normalize_folds_case_and_accents() {
+        assert_eq!(normalize("Café"), "cafe");
+        assert_eq!(normalize("CRÈME Brûlée"), "creme brulee");
+        assert_eq!(normalize("Ñandú"), "nandu");
+    }
+
+    #[test]
+    fn normalize_handles_decomposed_and_compatibility_forms() {
+        // "e" + tanda aksen terpisah (NFD) sama dengan "é"
+        assert_eq!(normalize("Cafe\u{301}"), normalize("Café"));
+        // Huruf lebar penuh dan ligatur dipetakan oleh NFKC
+        assert_eq!(normalize("ＲＵＳＴ"), "rust");
+        assert_eq!(normalize("ﬁle"), "file");
+    }
+
+    #[test]
+    fn normalize_applies_special_case_folding() {
+        assert_eq!(normalize("Straße"), "strasse");
+        assert_eq!(normalize("ΟΔΟΣ"), normalize("οδος"));
+    }
+
+    #[test]
+    fn word_spans_keep_original_byte_ranges() {
+        let text = "Ngopi di Café ＲＵＳＴ";
+        let spans = word_spans(text);
+        let words: Vec<&str> = spans.iter().map(|(_, _, w)| w.as_str()).collect();
+        assert_eq!(words, vec!["ngopi", "di", "cafe", "rust"]);
+        assert_eq!(&text[spans[2].0..spans[2].1], "Café");
+        assert_eq!(&text[spans[3].0..spans[3].1], "ＲＵＳＴ");
+    }
+
+    #[test]
+    fn decomposed_accents_stay_inside_the_word() {
+        assert_eq!(words("Cafe\u{301} ok"), vec!["cafe", "ok"]);
+    }
+}
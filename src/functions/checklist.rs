@@ -0,0 +1,212 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use chrono::Utc;
+use std::collections::HashMap;
+use crate::functions::utils::{
+    decrypt_data, decrypt_with_passphrase, encrypt_data, encrypt_with_passphrase, read_input, read_passphrase,
+};
+use crate::functions::validation::{print_errors, validate_checklist_item};
+
+pub struct ChecklistItem {
+    pub id: i64,
+    pub text: String,
+    pub done: bool,
+}
+
+// Jumlah item selesai dan total per catatan, tanpa perlu mendekripsi isi item
+pub fn checklist_map(conn: &Connection) -> anyhow::Result<HashMap<i32, (usize, usize)>> {
+    let mut stmt = conn.prepare("SELECT note_id, SUM(done), COUNT(*) FROM checklist_items GROUP BY note_id")
+        .context("Failed to prepare statement")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i32>(0)?, (row.get::<_, i64>(1)? as usize, row.get::<_, i64>(2)? as usize)))
+    }).context("Failed to count checklist items")?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+// Item catatan rahasia disegel dengan passphrase catatan, sama seperti judul dan isinya
+pub fn load_items(
+    conn: &Connection,
+    key: &Key<Aes256Gcm>,
+    note_id: i32,
+    passphrase: Option<&str>,
+) -> anyhow::Result<Vec<ChecklistItem>> {
+    let mut stmt = conn.prepare("SELECT id, text, done FROM checklist_items WHERE note_id = ?1 ORDER BY position, id")
+        .context("Failed to prepare statement")?;
+    let rows = stmt.query_and_then(params![note_id], |row| -> anyhow::Result<ChecklistItem> {
+        let encrypted_text: Vec<u8> = row.get(1).context("Failed to get checklist item from row")?;
+        let text = decrypt_data(&encrypted_text, key).context("Failed to decrypt checklist item")?;
+        let text = match passphrase {
+            Some(passphrase) => decrypt_with_passphrase(&text, passphrase)?,
+            None => text,
+        };
+        Ok(ChecklistItem {
+            id: row.get(0).context("Failed to get checklist item id from row")?,
+            text,
+            done: row.get(2).context("Failed to get checklist item status from row")?,
+        })
+    }).context("Failed to query checklist items")?;
+    rows.collect()
+}
+
+// Teks item dalam bentuk tersimpan: terenkripsi passphrase (untuk catatan rahasia) lalu dengan kunci utama
+pub fn add_item(
+    conn: &Connection,
+    key: &Key<Aes256Gcm>,
+    note_id: i32,
+    text: &str,
+    done: bool,
+    passphrase: Option<&str>,
+) -> anyhow::Result<()> {
+    let text = match passphrase {
+        Some(passphrase) => encrypt_with_passphrase(text, passphrase)?,
+        None => text.to_string(),
+    };
+    conn.execute(
+        "INSERT INTO checklist_items (note_id, position, text, done)
+         VALUES (?1, (SELECT COALESCE(MAX(position), 0) + 1 FROM checklist_items WHERE note_id = ?1), ?2, ?3)",
+        params![note_id, encrypt_data(&text, key)?, done],
+    ).context("Failed to insert checklist item")?;
+    Ok(())
+}
+
+// Dipanggil saat catatan dihapus
+pub fn remove_note_items(conn: &Connection, note_id: i32) -> anyhow::Result<()> {
+    conn.execute("DELETE FROM checklist_items WHERE note_id = ?1", params![note_id])
+        .context("Failed to delete checklist items")?;
+    Ok(())
+}
+
+// Menyimpan urutan baru dengan menomori ulang posisi semua item
+fn save_order(conn: &Connection, items: &[ChecklistItem]) -> anyhow::Result<()> {
+    for (position, item) in items.iter().enumerate() {
+        conn.execute(
+            "UPDATE checklist_items SET position = ?1 WHERE id = ?2",
+            params![position as i64 + 1, item.id],
+        ).context("Failed to reorder checklist item")?;
+    }
+    Ok(())
+}
+
+fn touch_note(conn: &Connection, note_id: i32) -> anyhow::Result<()> {
+    conn.execute(
+        "UPDATE notes SET modifiedAt = ?1 WHERE id = ?2",
+        params![Utc::now().to_rfc3339(), note_id],
+    ).context("Failed to update note")?;
+    Ok(())
+}
+
+fn print_items(items: &[ChecklistItem]) {
+    let done = items.iter().filter(|i| i.done).count();
+    println!("\nChecklist ({}/{} selesai):", done, items.len());
+    for (number, item) in items.iter().enumerate() {
+        println!("{:>3}. [{}] {}", number + 1, if item.done { "x" } else { " " }, item.text);
+    }
+    if items.is_empty() {
+        println!("Belum ada item.");
+    }
+}
+
+// Nomor item seperti yang ditampilkan (mulai dari 1), dikembalikan sebagai indeks
+fn read_item_number(prompt: &str, len: usize) -> anyhow::Result<Option<usize>> {
+    println!("{}", prompt);
+    let mut input = String::new();
+    read_input(&mut input)?;
+    match input.trim().parse::<usize>() {
+        Ok(number) if (1..=len).contains(&number) => Ok(Some(number - 1)),
+        _ => {
+            println!("Nomor item tidak valid!");
+            Ok(None)
+        }
+    }
+}
+
+pub fn checklist_menu(conn: &Connection, key: &Key<Aes256Gcm>, note_id: i32) -> anyhow::Result<()> {
+    let note: Option<(Vec<u8>, bool)> = conn.query_row(
+        "SELECT note, secret FROM notes WHERE id = ?1 AND deletedAt IS NULL",
+        params![note_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional().context("Failed to read note")?;
+    let Some((encrypted_title, secret)) = note else {
+        println!("Catatan dengan ID {} tidak ditemukan!", note_id);
+        return Ok(());
+    };
+    let passphrase = if secret {
+        let Some(passphrase) = read_passphrase(false)? else {
+            return Ok(());
+        };
+        let sealed_title = decrypt_data(&encrypted_title, key)?;
+        if decrypt_with_passphrase(&sealed_title, &passphrase).is_err() {
+            println!("Passphrase salah!");
+            return Ok(());
+        }
+        Some(passphrase)
+    } else {
+        None
+    };
+    let passphrase = passphrase.as_deref();
+
+    loop {
+        let mut items = load_items(conn, key, note_id, passphrase)?;
+        print_items(&items);
+        println!("\nMenu Checklist:");
+        println!("1. Tambah Item");
+        println!("2. Tandai Selesai/Belum");
+        println!("3. Pindahkan Item");
+        println!("4. Hapus Item");
+        println!("5. Kembali");
+        println!("Pilih opsi (1-5): ");
+
+        let mut choice = String::new();
+        read_input(&mut choice)?;
+        let choice: i32 = choice.trim().parse().unwrap_or(0);
+
+        match choice {
+            1 => {
+                println!("Masukkan item baru: ");
+                let mut text = String::new();
+                read_input(&mut text)?;
+                match validate_checklist_item(&text) {
+                    Ok(text) => add_item(conn, key, note_id, &text, false, passphrase)?,
+                    Err(e) => {
+                        print_errors(&[e]);
+                        continue;
+                    }
+                }
+            }
+            2 => {
+                let Some(index) = read_item_number("Nomor item yang akan ditandai: ", items.len())? else {
+                    continue;
+                };
+                conn.execute(
+                    "UPDATE checklist_items SET done = ?1 WHERE id = ?2",
+                    params![!items[index].done, items[index].id],
+                ).context("Failed to toggle checklist item")?;
+            }
+            3 => {
+                let Some(from) = read_item_number("Nomor item yang akan dipindahkan: ", items.len())? else {
+                    continue;
+                };
+                let Some(to) = read_item_number("Pindahkan ke nomor: ", items.len())? else {
+                    continue;
+                };
+                let item = items.remove(from);
+                items.insert(to, item);
+                save_order(conn, &items)?;
+            }
+            4 => {
+                let Some(index) = read_item_number("Nomor item yang akan dihapus: ", items.len())? else {
+                    continue;
+                };
+                conn.execute("DELETE FROM checklist_items WHERE id = ?1", params![items[index].id])
+                    .context("Failed to delete checklist item")?;
+            }
+            5 => return Ok(()),
+            _ => {
+                println!("Pilihan tidak valid!");
+                continue;
+            }
+        }
+        touch_note(conn, note_id)?;
+    }
+}
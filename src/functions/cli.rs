@@ -0,0 +1,29 @@
+use rusqlite::Connection;
+use aes_gcm::{Aes256Gcm, Key};
+use crate::functions::agenda::show_agenda;
+use crate::functions::saved_searches::run_saved_search;
+use crate::functions::search_notes::{SearchOptions, SortOrder, print_fuzzy_results};
+
+// Mode non-interaktif: `notes_app_rust <perintah> [argumen...]`
+pub fn run_cli(conn: &mut Connection, key: &Key<Aes256Gcm>, args: &[String]) -> anyhow::Result<()> {
+    match args.first().map(String::as_str) {
+        Some("fuzzy") if args.len() > 1 => {
+            let options = SearchOptions { sort: SortOrder::Relevance, snippet: false, stemming: false };
+            print_fuzzy_results(conn, key, &args[1..].join(" "), options)
+        }
+        Some("saved") if args.len() > 1 => run_saved_search(conn, key, &args[1..].join(" ")),
+        Some("agenda") => show_agenda(conn, key),
+        _ => {
+            print_usage();
+            Ok(())
+        }
+    }
+}
+
+fn print_usage() {
+    println!("Penggunaan:");
+    println!("  notes_app_rust                 Jalankan menu interaktif");
+    println!("  notes_app_rust fuzzy <kata>    Cari catatan yang mirip (toleran salah ketik)");
+    println!("  notes_app_rust saved <nama>    Jalankan pencarian tersimpan");
+    println!("  notes_app_rust agenda          Tampilkan catatan bertenggat");
+}
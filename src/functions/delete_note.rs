@@ -1,14 +1,57 @@
 use rusqlite::{Connection, params};
+use aes_gcm::{Aes256Gcm, Key};
 use anyhow::Context;
+use chrono::Utc;
+use crate::functions::links::LinkIndex;
+use crate::functions::trash::retention_days;
+use crate::functions::utils::{NOTE_COLUMNS, note_from_row, read_input};
 
-pub fn delete_note(conn: &Connection) -> anyhow::Result<()> {
-    println!("Masukkan ID catatan yang akan dihapus: ");
-    let mut id = String::new();
-    std::io::stdin().read_line(&mut id)?;
-    let id: i32 = id.trim().parse().unwrap_or(0);
+// Catatan tidak langsung dihapus, tetapi dipindahkan ke tempat sampah
+pub fn delete_note(conn: &Connection, key: &Key<Aes256Gcm>, provided_id: Option<i32>) -> anyhow::Result<()> {
+    let id = match provided_id {
+        Some(id) => id,
+        None => {
+            println!("Masukkan ID catatan yang akan dihapus: ");
+            let mut id = String::new();
+            read_input(&mut id)?;
+            id.trim().parse().unwrap_or(0)
+        }
+    };
 
-    conn.execute("DELETE FROM notes WHERE id = ?1", params![id])
-        .context("Failed to delete note")?;
-    println!("Catatan dengan ID {} berhasil dihapus!", id);
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM notes WHERE id = ?1 AND deletedAt IS NULL", NOTE_COLUMNS))
+        .context("Failed to prepare statement")?;
+    let Some(note) = stmt.query_and_then(params![id], |row| note_from_row(row, key))
+        .context("Failed to query note by ID")?
+        .next()
+        .transpose()?
+    else {
+        println!("Catatan dengan ID {} tidak ditemukan!", id);
+        return Ok(());
+    };
+
+    // Tautan ke catatan ini akan rusak selama catatan ada di tempat sampah
+    let backlinks = LinkIndex::load(conn, key)?.backlinks(id).len();
+    if backlinks > 0 {
+        println!("Perhatian: {} catatan lain menautkan ke catatan ini, tautannya akan rusak.", backlinks);
+    }
+    println!("Pindahkan catatan '{}' ke tempat sampah? (y/N): ", note.list_label());
+    let mut confirm = String::new();
+    read_input(&mut confirm)?;
+    if !confirm.trim().eq_ignore_ascii_case("y") {
+        println!("Catatan tidak dihapus.");
+        return Ok(());
+    }
+
+    conn.execute(
+        "UPDATE notes SET deletedAt = ?1 WHERE id = ?2",
+        params![Utc::now().to_rfc3339(), id],
+    ).context("Failed to delete note")?;
+    match retention_days() {
+        0 => println!("Catatan dengan ID {} dipindahkan ke tempat sampah.", id),
+        days => println!(
+            "Catatan dengan ID {} dipindahkan ke tempat sampah dan akan dihapus permanen setelah {} hari.",
+            id, days
+        ),
+    }
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,8 +1,19 @@
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
 use aes_gcm::{Aes256Gcm, Key};
 use anyhow::Context;
-use chrono::Utc;
-use crate::functions::utils::encrypt_data;
+use chrono::{DateTime, Utc};
+use crate::functions::blind_index::index_note;
+use crate::functions::folders::move_note;
+use crate::functions::priorities::Priorities;
+use crate::functions::revisions::save_revision;
+use crate::functions::tags::{add_tags, note_tags, parse_tag_list, remove_tags};
+use crate::functions::utils::{
+    decrypt_data, decrypt_with_passphrase, encrypt_body, encrypt_data, encrypt_with_passphrase, full_text, read_body,
+    format_due, read_input, read_passphrase,
+};
+use crate::functions::validation::{
+    limits, print_errors, validate_body, validate_due, validate_folder, validate_title,
+};
 
 pub fn edit_note(conn: &Connection, key: &Key<Aes256Gcm>, provided_id: Option<i32>) -> anyhow::Result<()> {
     let id = match provided_id {
@@ -10,58 +21,176 @@ pub fn edit_note(conn: &Connection, key: &Key<Aes256Gcm>, provided_id: Option<i3
         None => {
             println!("Masukkan ID catatan yang akan diedit: ");
             let mut id = String::new();
-            std::io::stdin().read_line(&mut id)?;
+            read_input(&mut id)?;
             id.trim().parse().unwrap_or(0)
         }
     };
 
-    println!("\nMasukkan catatan baru (max 255 char, kosongkan untuk tidak mengubah): ");
-    let mut note = String::new();
-    std::io::stdin().read_line(&mut note)?;
-    let note = note.trim();
+    let existing: Option<(Vec<u8>, Option<Vec<u8>>, bool)> = conn.query_row(
+        "SELECT note, body, secret FROM notes WHERE id = ?1 AND deletedAt IS NULL",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    ).optional().context("Failed to read note")?;
+    let Some((encrypted_title, encrypted_body, secret)) = existing else {
+        println!("Catatan dengan ID {} tidak ditemukan!", id);
+        return Ok(());
+    };
+    let old_title = decrypt_data(&encrypted_title, key)?;
+    let old_body = encrypted_body.map(|b| decrypt_data(&b, key)).transpose()?.unwrap_or_default();
+
+    let limits = limits();
+    println!("\nMasukkan judul baru (max {} karakter, kosongkan untuk tidak mengubah): ", limits.title_max);
+    let mut title = String::new();
+    read_input(&mut title)?;
+    let title = title.trim();
+
+    println!(
+        "Masukkan isi baru (max {} karakter), akhiri dengan baris berisi '.' saja (langsung '.' untuk tidak mengubah, :editor untuk mengedit isi lama): ",
+        limits.body_max
+    );
+    // Isi catatan rahasia masih terenkripsi passphrase, jadi editor dibuka kosong
+    let body = read_body(if secret { "" } else { &old_body })?;
+
+    // Hanya field yang diubah yang diperiksa
+    let title = (!title.is_empty()).then(|| validate_title(title));
+    let body = body.map(|b| validate_body(&b));
+    let errors: Vec<_> = title.iter().chain(body.iter()).filter_map(|r| r.as_ref().err()).collect();
+    if !errors.is_empty() {
+        print_errors(&errors);
+        println!("Catatan tidak diubah.");
+        return Ok(());
+    }
+    let mut new_title = title.and_then(Result::ok);
+    let mut new_body = body.and_then(Result::ok);
 
-    println!("Masukkan prioritas baru (1: Tinggi, 2: Sedang, 3: Rendah, 0: Tidak ubah): ");
+    // Catatan rahasia hanya bisa diganti isinya dengan passphrase yang sama
+    if secret && (new_title.is_some() || new_body.is_some()) {
+        let Some(passphrase) = read_passphrase(false)? else {
+            return Ok(());
+        };
+        if decrypt_with_passphrase(&old_title, &passphrase).is_err() {
+            println!("Passphrase salah, catatan tidak diubah.");
+            return Ok(());
+        }
+        new_title = new_title.map(|t| encrypt_with_passphrase(&t, &passphrase)).transpose()?;
+        new_body = new_body
+            .map(|b| if b.is_empty() { Ok(b) } else { encrypt_with_passphrase(&b, &passphrase) })
+            .transpose()?;
+    }
+
+    let priorities = Priorities::load(conn, key)?;
+    println!("Masukkan prioritas baru ({}, 0: Tidak ubah): ", priorities.options());
     let mut prio_choice = String::new();
-    std::io::stdin().read_line(&mut prio_choice)?;
-    let prio_choice: i32 = prio_choice.trim().parse().unwrap_or(0);
+    read_input(&mut prio_choice)?;
 
-    let priority = match prio_choice {
-        1 => Some("Tinggi"),
-        2 => Some("Sedang"),
-        3 => Some("Rendah"),
-        0 => None,
-        _ => {
-            println!("Pilihan tidak valid, prioritas tidak diubah.");
-            None
+    let priority = match prio_choice.trim() {
+        "" | "0" => None,
+        choice => {
+            let priority = priorities.find(choice);
+            if priority.is_none() {
+                println!("Pilihan tidak valid, prioritas tidak diubah.");
+            }
+            priority
         }
     };
 
-    // Format datetime tanpa nanodetik
-    let modified_at = Utc::now().to_rfc3339();
+    let current_tags = note_tags(conn, key, id)?;
+    if !current_tags.is_empty() {
+        println!("Tag saat ini: {}", current_tags.join(", "));
+    }
+    println!("Tambah tag (pisahkan dengan koma, kosongkan jika tidak ada): ");
+    let mut tag_input = String::new();
+    read_input(&mut tag_input)?;
+    let added_tags = parse_tag_list(&tag_input);
+    println!("Hapus tag (pisahkan dengan koma, kosongkan jika tidak ada): ");
+    let mut tag_input = String::new();
+    read_input(&mut tag_input)?;
+    let removed_tags = parse_tag_list(&tag_input);
+    let tags_changed = !added_tags.is_empty() || !removed_tags.is_empty();
 
-    if !note.is_empty() && priority.is_some() {
-        let encrypted_note = encrypt_data(note, key)?;
-        let encrypted_priority = encrypt_data(priority.unwrap(), key)?;
-        conn.execute(
-            "UPDATE notes SET note = ?1, priority = ?2, modifiedAt = ?3 WHERE id = ?4",
-            params![encrypted_note, encrypted_priority, modified_at, id],
-        ).context("Failed to update note and priority")?;
-    } else if !note.is_empty() {
-        let encrypted_note = encrypt_data(note, key)?;
-        conn.execute(
-            "UPDATE notes SET note = ?1, modifiedAt = ?2 WHERE id = ?3",
-            params![encrypted_note, modified_at, id],
-        ).context("Failed to update note")?;
-    } else if let Some(p) = priority {
-        let encrypted_priority = encrypt_data(p, key)?;
-        conn.execute(
-            "UPDATE notes SET priority = ?1, modifiedAt = ?2 WHERE id = ?3",
-            params![encrypted_priority, modified_at, id],
-        ).context("Failed to update priority")?;
+    println!("Pindahkan ke folder (kosongkan untuk tidak mengubah, '/' untuk root): ");
+    let mut input = String::new();
+    read_input(&mut input)?;
+    let new_folder = if input.trim().is_empty() {
+        None
     } else {
+        match validate_folder(&input) {
+            Ok(folder) => Some(folder),
+            Err(e) => {
+                print_errors(&[e]);
+                println!("Folder tidak diubah.");
+                None
+            }
+        }
+    };
+
+    let old_due: Option<String> = conn.query_row("SELECT dueAt FROM notes WHERE id = ?1", params![id], |row| row.get(0))
+        .context("Failed to read due date")?;
+    let old_due = old_due.and_then(|s| DateTime::parse_from_rfc3339(&s).ok()).map(|dt| dt.with_timezone(&Utc));
+    if let Some(due_at) = old_due {
+        println!("Tenggat saat ini: {}", format_due(due_at));
+    }
+    println!("Masukkan tenggat baru (YYYY-MM-DD atau YYYY-MM-DD HH:MM, kosongkan untuk tidak mengubah, '-' untuk menghapus): ");
+    let mut due_input = String::new();
+    read_input(&mut due_input)?;
+    // Some(None) berarti tenggat dihapus
+    let new_due = match due_input.trim() {
+        "" => None,
+        "-" => Some(None),
+        input => match validate_due(input) {
+            Ok(due_at) => Some(Some(due_at.to_rfc3339())),
+            Err(e) => {
+                print_errors(&[e]);
+                println!("Tenggat tidak diubah.");
+                None
+            }
+        },
+    };
+
+    if new_title.is_none() && new_body.is_none() && priority.is_none() && new_due.is_none()
+        && !tags_changed && new_folder.is_none()
+    {
         println!("Tidak ada perubahan yang dibuat.");
         return Ok(());
     }
+
+    // Format datetime tanpa nanodetik
+    let modified_at = Utc::now().to_rfc3339();
+    let encrypted_title = new_title.as_deref().map(|t| encrypt_data(t, key)).transpose()?;
+    let encrypted_body = new_body.as_deref().map(|b| encrypt_body(b, key)).transpose()?.flatten();
+    let encrypted_priority = priority.map(|p| encrypt_data(p, key)).transpose()?;
+    // Semua input sudah dibaca; perubahan disimpan sekaligus agar tidak ada edit yang setengah jalan
+    let tx = conn.unchecked_transaction().context("Failed to start transaction")?;
+    save_revision(&tx, id)?;
+    if tags_changed {
+        remove_tags(&tx, key, id, &removed_tags)?;
+        add_tags(&tx, key, id, &added_tags)?;
+    }
+    if let Some(folder) = &new_folder {
+        move_note(&tx, key, id, folder)?;
+    }
+    tx.execute(
+        "UPDATE notes SET
+             note = COALESCE(?1, note),
+             body = CASE WHEN ?2 THEN ?3 ELSE body END,
+             priority = COALESCE(?4, priority),
+             dueAt = CASE WHEN ?5 THEN ?6 ELSE dueAt END,
+             modifiedAt = ?7
+         WHERE id = ?8",
+        params![
+            encrypted_title, new_body.is_some(), encrypted_body, encrypted_priority,
+            new_due.is_some(), new_due.flatten(), modified_at, id,
+        ],
+    ).context("Failed to update note")?;
+
+    if !secret && (new_title.is_some() || new_body.is_some()) {
+        let text = full_text(
+            new_title.as_deref().unwrap_or(&old_title),
+            new_body.as_deref().unwrap_or(&old_body),
+        );
+        index_note(&tx, key, id, &text)?;
+    }
+    tx.commit().context("Failed to commit transaction")?;
     println!("Catatan dengan ID {} berhasil diperbarui!", id);
     Ok(())
-}
\ No newline at end of file
+}
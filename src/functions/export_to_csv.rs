@@ -2,56 +2,60 @@ use rusqlite::Connection;
 use aes_gcm::{Aes256Gcm, Key};
 use anyhow::Context;
 use csv::WriterBuilder;
-use std::fs::File;
-use chrono::{DateTime, Utc};
-use crate::functions::utils::{Note, decrypt_data};
+use std::fs;
+use crate::functions::attachments::{attachments_path, export_attachments};
+use crate::functions::checklist::load_items;
+use crate::functions::tags::note_tag_map;
+use crate::functions::utils::{NOTE_COLUMNS, note_from_row, sign_export};
 
 pub fn export_to_csv(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
-    let mut stmt = conn.prepare("SELECT id, note, priority, createdAt, modifiedAt FROM notes")
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM notes", NOTE_COLUMNS))
         .context("Failed to prepare statement")?;
     let note_iter = stmt.query_and_then(
         [],
-        |row| -> anyhow::Result<Note> {
-            let encrypted_note: Vec<u8> = row.get(1)?;
-            let encrypted_priority: Vec<u8> = row.get(2)?;
-            let note = decrypt_data(&encrypted_note, key)?;
-            let priority = decrypt_data(&encrypted_priority, key)?;
-            let created_at_str: String = row.get(3)?;
-            let modified_at_str: Option<String> = row.get(4)?;
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map(|dt| dt.with_timezone(&Utc))?;
-            let modified_at = modified_at_str.map(|s| {
-                DateTime::parse_from_rfc3339(&s)
-                    .map(|dt| dt.with_timezone(&Utc))
-            }).transpose()?;
-            Ok(Note {
-                id: row.get(0)?,
-                note,
-                priority,
-                created_at,
-                modified_at,
-            })
-        }
+        |row| note_from_row(row, key),
     ).context("Failed to query notes for export")?;
 
-    let file = File::create("notes_export.csv").context("Failed to create CSV file")?;
-    let mut wtr = WriterBuilder::new().from_writer(file);
+    let mut wtr = WriterBuilder::new().from_writer(Vec::new());
 
-    wtr.write_record(&["id", "note", "priority", "createdAt", "modifiedAt"])
+    wtr.write_record(["id", "title", "priority", "createdAt", "modifiedAt", "secret", "body", "tags", "folder", "dueAt", "checklist", "pinned", "archived", "deletedAt"])
         .context("Failed to write CSV header")?;
 
+    let mut tag_map = note_tag_map(conn, key)?;
     for note in note_iter {
         let note = note?;
+        let tags = tag_map.remove(&note.id).unwrap_or_default();
+        // Satu item per baris, "[x] " untuk item yang sudah selesai
+        let checklist: Vec<String> = load_items(conn, key, note.id, None)?
+            .into_iter()
+            .map(|item| format!("[{}] {}", if item.done { "x" } else { " " }, item.text))
+            .collect();
         wtr.write_record(&[
             note.id.to_string(),
-            note.note,
+            note.title,
             note.priority,
             note.created_at.to_rfc3339(),
             note.modified_at.map_or(String::new(), |dt| dt.to_rfc3339()),
+            // Catatan rahasia tetap diekspor dalam bentuk terenkripsi passphrase
+            (note.secret as i32).to_string(),
+            note.body,
+            tags.join(","),
+            note.folder,
+            note.due_at.map_or(String::new(), |dt| dt.to_rfc3339()),
+            checklist.join("\n"),
+            (note.pinned as i32).to_string(),
+            (note.archived as i32).to_string(),
+            note.deleted_at.map_or(String::new(), |dt| dt.to_rfc3339()),
         ]).context("Failed to write CSV record")?;
     }
 
-    wtr.flush().context("Failed to flush CSV writer")?;
+    let data = wtr.into_inner().context("Failed to flush CSV writer")?;
+    let signature = sign_export(&data, key)?;
+    fs::write("notes_export.csv", &data).context("Failed to write CSV file")?;
+    fs::write("notes_export.csv.sig", &signature).context("Failed to write signature file")?;
+    let attachments = export_attachments(conn, key, "notes_export.csv", &signature)?;
     println!("Data berhasil diekspor ke 'notes_export.csv'!");
+    println!("Tanda tangan disimpan di 'notes_export.csv.sig'.");
+    println!("{} lampiran (terenkripsi) disimpan di '{}'.", attachments, attachments_path("notes_export.csv"));
     Ok(())
 }
\ No newline at end of file
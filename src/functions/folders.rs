@@ -0,0 +1,170 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use hmac::Mac;
+use std::collections::HashMap;
+use crate::functions::query_filter::parse_filter;
+use crate::functions::show_all_notes::print_all_notes;
+use crate::functions::utils::{decrypt_data, derive_mac, encrypt_data, read_input};
+use crate::functions::validation::{FieldError, print_errors, validate_folder};
+
+// Path folder dienkripsi; HMAC dari path huruf kecil dipakai untuk mencari folder yang sama
+fn folder_hash(key: &Key<Aes256Gcm>, path: &str) -> anyhow::Result<Vec<u8>> {
+    let mut mac = derive_mac(key, "notes_app_rust folder paths")?;
+    mac.update(path.to_lowercase().as_bytes());
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+// `path` sama dengan `scope` atau berada di dalamnya; scope kosong berarti semua folder
+pub fn in_folder(path: &str, scope: &str) -> bool {
+    let (path, scope) = (path.to_lowercase(), scope.to_lowercase());
+    scope.is_empty() || path == scope || path.starts_with(&format!("{}/", scope))
+}
+
+fn find_folder(conn: &Connection, key: &Key<Aes256Gcm>, path: &str) -> anyhow::Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM folders WHERE path_hash = ?1",
+        params![folder_hash(key, path)?],
+        |row| row.get(0),
+    ).optional().context("Failed to look up folder")
+}
+
+// Folder induk ikut dibuat agar pohon folder selalu lengkap; path kosong berarti root
+pub fn get_or_create_folder(conn: &Connection, key: &Key<Aes256Gcm>, path: &str) -> anyhow::Result<Option<i64>> {
+    let mut folder_id = None;
+    let mut current = String::new();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        if !current.is_empty() {
+            current.push('/');
+        }
+        current.push_str(segment);
+        folder_id = Some(match find_folder(conn, key, &current)? {
+            Some(id) => id,
+            None => {
+                conn.execute(
+                    "INSERT INTO folders (path, path_hash) VALUES (?1, ?2)",
+                    params![encrypt_data(&current, key)?, folder_hash(key, &current)?],
+                ).context("Failed to insert folder")?;
+                conn.last_insert_rowid()
+            }
+        });
+    }
+    Ok(folder_id)
+}
+
+pub fn move_note(conn: &Connection, key: &Key<Aes256Gcm>, note_id: i32, path: &str) -> anyhow::Result<()> {
+    let folder_id = get_or_create_folder(conn, key, path)?;
+    conn.execute(
+        "UPDATE notes SET folder_id = ?1 WHERE id = ?2",
+        params![folder_id, note_id],
+    ).context("Failed to move note")?;
+    Ok(())
+}
+
+// Membaca path dari input; "/" berarti root
+pub fn read_folder(prompt: &str) -> anyhow::Result<Result<String, FieldError>> {
+    println!("{}", prompt);
+    let mut path = String::new();
+    read_input(&mut path)?;
+    Ok(validate_folder(&path))
+}
+
+fn load_folders(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare("SELECT id, path FROM folders")
+        .context("Failed to prepare statement")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?)))
+        .context("Failed to query folders")?;
+    let mut folders = Vec::new();
+    for row in rows {
+        let (id, encrypted_path) = row?;
+        folders.push((id, decrypt_data(&encrypted_path, key).context("Failed to decrypt folder")?));
+    }
+    folders.sort_by_key(|(_, path)| path.to_lowercase());
+    Ok(folders)
+}
+
+// Pohon folder dengan jumlah catatan langsung dan total termasuk subfolder
+fn print_tree(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    let folders = load_folders(conn, key)?;
+    let mut stmt = conn.prepare("SELECT folder_id, COUNT(*) FROM notes WHERE deletedAt IS NULL GROUP BY folder_id")
+        .context("Failed to prepare statement")?;
+    let counts: HashMap<Option<i64>, i64> = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .context("Failed to count notes per folder")?
+        .collect::<Result<_, _>>()?;
+
+    println!("\nPohon Folder:");
+    println!("/ ({} catatan di root)", counts.get(&None).copied().unwrap_or(0));
+    for (id, path) in &folders {
+        let depth = path.matches('/').count();
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let total: i64 = folders.iter()
+            .filter(|(_, p)| in_folder(p, path))
+            .map(|(id, _)| counts.get(&Some(*id)).copied().unwrap_or(0))
+            .sum();
+        let direct = counts.get(&Some(*id)).copied().unwrap_or(0);
+        println!("{}{}/ ({} catatan, {} termasuk subfolder)", "  ".repeat(depth + 1), name, direct, total);
+    }
+    if folders.is_empty() {
+        println!("Belum ada folder.");
+    }
+    Ok(())
+}
+
+pub fn folders_menu(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    print_tree(conn, key)?;
+    println!("\nMenu:");
+    println!("1. Tampilkan Catatan dalam Folder");
+    println!("2. Pindahkan Catatan ke Folder");
+    println!("3. Buat Folder");
+    println!("4. Kembali ke Menu Utama");
+    println!("Pilih opsi (1-4): ");
+
+    let mut choice = String::new();
+    read_input(&mut choice)?;
+    let choice: i32 = choice.trim().parse().unwrap_or(0);
+
+    match choice {
+        1 => {
+            let path = match read_folder("Masukkan path folder (mis. kerja/klien): ")? {
+                Ok(path) => path,
+                Err(e) => {
+                    print_errors(&[e]);
+                    return Ok(());
+                }
+            };
+            // Folder dan subfoldernya, sama dengan filter `folder:` di pencarian
+            let filter = parse_filter(&format!("folder:{}", path)).map_err(anyhow::Error::msg)?;
+            print_all_notes(conn, key, Some(&filter))?;
+        }
+        2 => {
+            println!("Masukkan ID catatan yang akan dipindahkan: ");
+            let mut id = String::new();
+            read_input(&mut id)?;
+            let id: i32 = id.trim().parse().unwrap_or(0);
+            let exists: Option<i32> = conn.query_row("SELECT id FROM notes WHERE id = ?1 AND deletedAt IS NULL", params![id], |row| row.get(0))
+                .optional().context("Failed to read note")?;
+            if exists.is_none() {
+                println!("Catatan dengan ID {} tidak ditemukan!", id);
+                return Ok(());
+            }
+            match read_folder("Masukkan folder tujuan (mis. kerja/klien, '/' untuk root): ")? {
+                Ok(path) => {
+                    move_note(conn, key, id, &path)?;
+                    println!("Catatan dengan ID {} dipindahkan ke '/{}'.", id, path);
+                }
+                Err(e) => print_errors(&[e]),
+            }
+        }
+        3 => match read_folder("Masukkan path folder baru (mis. kerja/klien/acme): ")? {
+            Ok(path) if path.is_empty() => println!("Path folder tidak boleh kosong."),
+            Ok(path) => {
+                get_or_create_folder(conn, key, &path)?;
+                println!("Folder '/{}' berhasil dibuat!", path);
+            }
+            Err(e) => print_errors(&[e]),
+        },
+        4 => println!("Kembali ke menu utama."),
+        _ => println!("Pilihan tidak valid!"),
+    }
+    Ok(())
+}
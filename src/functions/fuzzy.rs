@@ -0,0 +1,135 @@
+use crate::functions::blind_index::words;
+use crate::functions::search_engine::Document;
+
+// Hasil pencarian fuzzy beserta pasangan (kata query, kata yang cocok)
+pub struct FuzzyMatch {
+    pub id: i32,
+    pub score: f64,
+    pub matched: Vec<(String, String)>,
+}
+
+// Jarak Levenshtein dengan transposisi dua huruf bersebelahan (Damerau terbatas)
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+// Kata pendek hanya boleh salah satu huruf, kata panjang lebih longgar
+fn max_distance(len: usize) -> usize {
+    match len {
+        0..=4 => 1,
+        5..=8 => 2,
+        _ => 3,
+    }
+}
+
+fn best_match<'a>(query_word: &str, words: &'a [String]) -> Option<(&'a String, f64)> {
+    let query_len = query_word.chars().count();
+    words.iter()
+        .filter_map(|word| {
+            let distance = edit_distance(query_word, word);
+            if distance > max_distance(query_len) {
+                return None;
+            }
+            let longest = query_len.max(word.chars().count()).max(1);
+            Some((word, 1.0 - distance as f64 / longest as f64))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+// Setiap kata query harus punya padanan dekat; skor adalah rata-rata kemiripan
+pub fn fuzzy_search(query: &str, documents: &[Document]) -> Vec<FuzzyMatch> {
+    let query_words = words(query);
+    if query_words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut results: Vec<FuzzyMatch> = documents.iter()
+        .filter_map(|doc| {
+            let mut matched = Vec::new();
+            let mut total = 0.0;
+            for query_word in &query_words {
+                let (word, similarity) = best_match(query_word, &doc.words)?;
+                matched.push((query_word.clone(), word.clone()));
+                total += similarity;
+            }
+            Some(FuzzyMatch {
+                id: doc.id,
+                score: total / query_words.len() as f64,
+                matched,
+            })
+        })
+        .collect();
+    results.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_counts_basic_edits() {
+        assert_eq!(edit_distance("rapat", "rapat"), 0);
+        assert_eq!(edit_distance("rapat", "rapt"), 1);
+        assert_eq!(edit_distance("rapat", "rapatt"), 1);
+        assert_eq!(edit_distance("rapat", "ripat"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn adjacent_transposition_costs_one() {
+        assert_eq!(edit_distance("rapat", "rpaat"), 1);
+        assert_eq!(edit_distance("laporan", "lapoarn"), 1);
+        // Versi terbatas: huruf yang sudah ditukar tidak diedit lagi
+        assert_eq!(edit_distance("ca", "abc"), 3);
+    }
+
+    #[test]
+    fn edit_distance_counts_characters_not_bytes() {
+        assert_eq!(edit_distance("café", "cafe"), 1);
+        assert_eq!(edit_distance("ｒｕｓｔ", "rust"), 4);
+    }
+
+    #[test]
+    fn fuzzy_search_tolerates_typos_within_limit() {
+        let documents = vec![
+            Document { id: 1, words: words("laporan pajak tahunan") },
+            Document { id: 2, words: words("rapat tim") },
+        ];
+        let results = fuzzy_search("lpaoran", &documents);
+        assert_eq!(results.iter().map(|m| m.id).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(results[0].matched, vec![("lpaoran".to_string(), "laporan".to_string())]);
+        // Kata pendek hanya boleh salah satu huruf
+        assert!(fuzzy_search("rpt", &documents).is_empty());
+    }
+
+    #[test]
+    fn every_query_word_needs_a_match() {
+        let documents = vec![Document { id: 1, words: words("rapat tim") }];
+        assert_eq!(fuzzy_search("rapat tin", &documents).len(), 1);
+        assert!(fuzzy_search("rapat direksi", &documents).is_empty());
+        assert!(fuzzy_search("", &documents).is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+use std::io::IsTerminal;
+use textwrap::wrap;
+
+const HIGHLIGHT_START: &str = "\x1B[1;33m";
+const HIGHLIGHT_END: &str = "\x1B[0m";
+// Jumlah karakter konteks di kiri dan kanan setiap kecocokan pada mode cuplikan
+const SNIPPET_CONTEXT: usize = 30;
+
+// Warna ANSI hanya dipakai jika output ke terminal, selain itu pakai penanda [..]
+pub fn use_color() -> bool {
+    std::io::stdout().is_terminal()
+}
+
+// Menggabungkan rentang byte yang bertumpuk dan mengurutkannya
+fn merge_ranges(ranges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut sorted: Vec<(usize, usize)> = ranges.iter().copied().filter(|(s, e)| s < e).collect();
+    sorted.sort();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in sorted {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+// Membungkus teks ke lebar kolom dengan rentang yang cocok disorot; tiap baris sudah diberi padding
+pub fn highlight_lines(text: &str, ranges: &[(usize, usize)], width: usize) -> Vec<String> {
+    let ranges = merge_ranges(ranges);
+    if !use_color() {
+        let mut marked = String::new();
+        let mut pos = 0;
+        for (start, end) in &ranges {
+            marked.push_str(&text[pos..*start]);
+            marked.push('[');
+            marked.push_str(&text[*start..*end]);
+            marked.push(']');
+            pos = *end;
+        }
+        marked.push_str(&text[pos..]);
+        return wrap(&marked, width).iter().map(|line| format!("{:<width$}", line)).collect();
+    }
+
+    // Baris hasil wrap dicari kembali posisinya di teks asli agar rentang bisa dipetakan
+    let mut pos = 0;
+    wrap(text, width).iter()
+        .map(|line| {
+            let line_start = text[pos..].find(line.as_ref()).map(|i| pos + i);
+            let padding = " ".repeat(width.saturating_sub(line.chars().count()));
+            let Some(line_start) = line_start else {
+                return format!("{}{}", line, padding);
+            };
+            let line_end = line_start + line.len();
+            pos = line_end;
+            let mut styled = String::new();
+            let mut cursor = line_start;
+            for (start, end) in &ranges {
+                let (start, end) = ((*start).max(line_start), (*end).min(line_end));
+                if start >= end {
+                    continue;
+                }
+                styled.push_str(&text[cursor..start]);
+                styled.push_str(HIGHLIGHT_START);
+                styled.push_str(&text[start..end]);
+                styled.push_str(HIGHLIGHT_END);
+                cursor = end;
+            }
+            styled.push_str(&text[cursor..line_end]);
+            styled + &padding
+        })
+        .collect()
+}
+
+// Mode cuplikan: hanya teks di sekitar kecocokan, rentang dipetakan ke posisi baru
+pub fn snippet(text: &str, ranges: &[(usize, usize)]) -> (String, Vec<(usize, usize)>) {
+    let ranges = merge_ranges(ranges);
+    if ranges.is_empty() {
+        return (text.to_string(), ranges);
+    }
+    let windows: Vec<(usize, usize)> = ranges.iter()
+        .map(|&(start, end)| {
+            let window_start = text[..start].char_indices().rev().nth(SNIPPET_CONTEXT - 1).map_or(0, |(i, _)| i);
+            let window_end = text[end..].char_indices().nth(SNIPPET_CONTEXT).map_or(text.len(), |(i, _)| end + i);
+            (window_start, window_end)
+        })
+        .collect();
+    let windows = merge_ranges(&windows);
+
+    let mut out = String::new();
+    let mut mapped = Vec::new();
+    for (index, (window_start, window_end)) in windows.iter().enumerate() {
+        if index > 0 {
+            out.push_str(" ... ");
+        } else if *window_start > 0 {
+            out.push_str("...");
+        }
+        let base = out.len();
+        out.push_str(&text[*window_start..*window_end]);
+        mapped.extend(ranges.iter()
+            .filter(|(start, end)| start >= window_start && end <= window_end)
+            .map(|(start, end)| (base + start - window_start, base + end - window_start)));
+    }
+    if windows.last().is_some_and(|(_, end)| *end < text.len()) {
+        out.push_str("...");
+    }
+    (out, mapped)
+}
@@ -2,26 +2,60 @@ use rusqlite::{Connection, params};
 use aes_gcm::{Aes256Gcm, Key};
 use anyhow::Context;
 use csv::ReaderBuilder;
-use std::fs::File;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 use chrono::{DateTime, Utc};
-use crate::functions::utils::encrypt_data;
+use crate::functions::attachments::{attachments_path, import_attachments, verify_attachment_export};
+use crate::functions::blind_index::rebuild_index;
+use crate::functions::utils::{encrypt_body, encrypt_data, read_input, sign_export, verify_export};
+use crate::functions::checklist::add_item;
+use crate::functions::folders::move_note;
+use crate::functions::priorities::Priorities;
+use crate::functions::tags::add_tags;
+use crate::functions::validation::{
+    print_errors, validate_body, validate_checklist_item, validate_folder, validate_priority, validate_tag,
+    validate_title,
+};
 
 pub fn import_from_csv(conn: &mut Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
     println!("Masukkan path file CSV untuk diimpor (default: 'notes_import.csv'): ");
     let mut path = String::new();
-    std::io::stdin().read_line(&mut path)?;
+    read_input(&mut path)?;
     let path = path.trim();
     let path = if path.is_empty() { "notes_import.csv" } else { path };
 
-    let file = File::open(path).context("Failed to open CSV file")?;
-    let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(file);
+    let data = fs::read(path).context("Failed to open CSV file")?;
+    let signature_path = format!("{}.sig", path);
+    let warning = match fs::read_to_string(&signature_path) {
+        Ok(signature) if verify_export(&data, &signature, key)? => None,
+        Ok(_) => Some("PERINGATAN: tanda tangan tidak cocok, file telah diubah atau berasal dari database lain."),
+        Err(_) => Some("PERINGATAN: file tidak memiliki tanda tangan, keaslian isinya tidak dapat diperiksa."),
+    };
+    if let Some(warning) = warning {
+        println!("{}", warning);
+        println!("Lanjutkan import? Semua catatan saat ini akan diganti. (y/N): ");
+        let mut confirm = String::new();
+        read_input(&mut confirm)?;
+        if !confirm.trim().eq_ignore_ascii_case("y") {
+            println!("Import dibatalkan.");
+            return Ok(());
+        }
+    }
+
+    let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(data.as_slice());
 
+    // Prioritas harus cocok dengan level yang diatur di database tujuan
+    let priorities = Priorities::load(conn, key)?;
     let mut records = Vec::new();
-    for result in rdr.records() {
+    let mut errors = Vec::new();
+    for (index, result) in rdr.records().enumerate() {
         let record = result.context("Failed to read CSV record")?;
+        // Nomor baris di file, baris pertama adalah header
+        let line = index + 2;
         let id: i32 = record.get(0).unwrap_or("0").parse().unwrap_or(0);
-        let note = record.get(1).unwrap_or("").to_string();
-        let priority = record.get(2).unwrap_or("Sedang").to_string();
+        let title = record.get(1).unwrap_or("");
+        let priority = record.get(2).filter(|p| !p.is_empty()).unwrap_or(priorities.default_name());
         let created_at = record.get(3)
             .map(|s| DateTime::parse_from_rfc3339(s).map(|dt| dt.with_timezone(&Utc)))
             .unwrap_or_else(|| Ok(Utc::now()))
@@ -31,30 +65,178 @@ pub fn import_from_csv(conn: &mut Connection, key: &Key<Aes256Gcm>) -> anyhow::R
             .map(|s| DateTime::parse_from_rfc3339(s).map(|dt| dt.with_timezone(&Utc)))
             .transpose()
             .context("Failed to parse modifiedAt from CSV")?;
+        let secret = record.get(5).is_some_and(|s| s.trim() == "1");
+        let pinned = record.get(11).is_some_and(|s| s.trim() == "1");
+        let archived = record.get(12).is_some_and(|s| s.trim() == "1");
+        let deleted_at = record.get(13)
+            .filter(|s| !s.is_empty())
+            .map(|s| DateTime::parse_from_rfc3339(s).map(|dt| dt.with_timezone(&Utc).to_rfc3339()))
+            .transpose()
+            .context("Failed to parse deletedAt from CSV")?;
+        // File export lama belum punya kolom body
+        let body = record.get(6).unwrap_or("");
+        let mut tags = Vec::new();
+        for tag in record.get(7).unwrap_or("").split(',').filter(|t| !t.trim().is_empty()) {
+            match validate_tag(tag) {
+                Ok(tag) => tags.push(tag),
+                Err(e) => errors.push(format!("Baris {}: {}", line, e)),
+            }
+        }
+
+        let folder = match validate_folder(record.get(8).unwrap_or("")) {
+            Ok(folder) => folder,
+            Err(e) => {
+                errors.push(format!("Baris {}: {}", line, e));
+                String::new()
+            }
+        };
+
+        let due_at = match record.get(9).filter(|s| !s.is_empty()).map(DateTime::parse_from_rfc3339) {
+            Some(Ok(dt)) => Some(dt.with_timezone(&Utc).to_rfc3339()),
+            Some(Err(_)) => {
+                errors.push(format!("Baris {}: Tenggat: format tidak valid, gunakan RFC 3339", line));
+                None
+            }
+            None => None,
+        };
+
+        let mut checklist = Vec::new();
+        for item in record.get(10).unwrap_or("").lines().filter(|l| !l.trim().is_empty()) {
+            let (done, text) = match item.strip_prefix("[x] ").or_else(|| item.strip_prefix("[X] ")) {
+                Some(text) => (true, text),
+                None => (false, item.strip_prefix("[ ] ").unwrap_or(item)),
+            };
+            // Item catatan rahasia juga berupa hex terenkripsi passphrase
+            let text = if secret { Ok(text.trim().to_string()) } else { validate_checklist_item(text) };
+            match text {
+                Ok(text) => checklist.push((text, done)),
+                Err(e) => errors.push(format!("Baris {}: {}", line, e)),
+            }
+        }
 
-        let encrypted_note = encrypt_data(&note, key)?;
+        // Isi catatan rahasia berupa hex terenkripsi passphrase, jadi hanya prioritas yang diperiksa
+        let checked = (
+            if secret { Ok(title.to_string()) } else { validate_title(title) },
+            if secret { Ok(body.to_string()) } else { validate_body(body) },
+            validate_priority(priority, &priorities),
+        );
+        let (title, body, priority) = match checked {
+            (Ok(title), Ok(body), Ok(priority)) => (title, body, priority),
+            (title, body, priority) => {
+                errors.extend(
+                    [title.err(), body.err(), priority.err()].into_iter().flatten()
+                        .map(|e| format!("Baris {}: {}", line, e)),
+                );
+                continue;
+            }
+        };
+
+        let encrypted_title = encrypt_data(&title, key)?;
+        let encrypted_body = encrypt_body(&body, key)?;
         let encrypted_priority = encrypt_data(&priority, key)?;
 
-        records.push((id, encrypted_note, encrypted_priority, created_at.to_rfc3339(), modified_at.map(|dt| dt.to_rfc3339())));
+        records.push((
+            id,
+            encrypted_title,
+            encrypted_body,
+            encrypted_priority,
+            created_at.to_rfc3339(),
+            modified_at.map(|dt| dt.to_rfc3339()),
+            secret,
+            tags,
+            folder,
+            due_at,
+            checklist,
+            pinned,
+            archived,
+            deleted_at,
+        ));
+    }
+
+    // Data yang tidak valid tidak disimpan sama sekali, database lama tetap utuh
+    if !errors.is_empty() {
+        print_errors(&errors);
+        println!("Import dibatalkan, tidak ada data yang diubah.");
+        return Ok(());
     }
 
+    // Lampiran ada di file terpisah yang terikat ke CSV ini; tanpa file itu lampiran yang ada akan hilang
+    let csv_signature = sign_export(&data, key)?;
+    let attachments_file = attachments_path(path);
+    let restore_attachments = if Path::new(&attachments_file).exists() {
+        if let Err(e) = verify_attachment_export(path, key, &csv_signature) {
+            println!("File lampiran '{}' tidak valid: {:#}", attachments_file, e);
+            println!("Import dibatalkan, tidak ada data yang diubah.");
+            return Ok(());
+        }
+        true
+    } else {
+        let existing: i64 = conn.query_row("SELECT COUNT(*) FROM attachments", [], |row| row.get(0))
+            .context("Failed to count attachments before import")?;
+        if existing > 0 {
+            println!("PERINGATAN: file lampiran '{}' tidak ditemukan.", attachments_file);
+            println!("{} lampiran yang ada akan dihapus permanen. Lanjutkan import? (y/N): ", existing);
+            let mut confirm = String::new();
+            read_input(&mut confirm)?;
+            if !confirm.trim().eq_ignore_ascii_case("y") {
+                println!("Import dibatalkan.");
+                return Ok(());
+            }
+        }
+        false
+    };
+
     let tx = conn.transaction().context("Failed to start transaction")?;
     tx.execute("DELETE FROM notes", []).context("Failed to clear table before import")?;
-    for (id, note, priority, created_at, modified_at) in records {
+    tx.execute_batch("DELETE FROM note_tags; DELETE FROM tags; DELETE FROM folders;")
+        .context("Failed to clear tags and folders before import")?;
+    tx.execute("DELETE FROM checklist_items", []).context("Failed to clear checklist items before import")?;
+    // Riwayat revisi tidak ikut diekspor, dan ID catatan lama bisa dipakai catatan lain
+    tx.execute("DELETE FROM note_revisions", []).context("Failed to clear revisions before import")?;
+    tx.execute_batch("DELETE FROM attachment_chunks; DELETE FROM attachments;")
+        .context("Failed to clear attachments before import")?;
+    // ID catatan di file ekspor -> ID catatan setelah import, untuk memulihkan lampiran
+    let mut note_ids: HashMap<i32, i32> = HashMap::new();
+    for (id, title, body, priority, created_at, modified_at, secret, tags, folder, due_at, checklist, pinned, archived, deleted_at) in records {
         if id == 0 {
             tx.execute(
-                "INSERT INTO notes (note, priority, createdAt, modifiedAt) VALUES (?1, ?2, ?3, ?4)",
-                params![note, priority, created_at, modified_at],
+                "INSERT INTO notes (note, body, priority, createdAt, modifiedAt, secret, dueAt) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![title, body, priority, created_at, modified_at, secret, due_at],
             ).context("Failed to insert note during import")?;
         } else {
             tx.execute(
-                "INSERT INTO notes (id, note, priority, createdAt, modifiedAt) VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![id, note, priority, created_at, modified_at],
+                "INSERT INTO notes (id, note, body, priority, createdAt, modifiedAt, secret, dueAt) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![id, title, body, priority, created_at, modified_at, secret, due_at],
             ).context("Failed to insert note with ID during import")?;
         }
+        let note_id = tx.last_insert_rowid() as i32;
+        if id != 0 {
+            note_ids.insert(id, note_id);
+        }
+        tx.execute(
+            "UPDATE notes SET pinned = ?1, archived = ?2, deletedAt = ?3 WHERE id = ?4",
+            params![pinned, archived, deleted_at, note_id],
+        ).context("Failed to restore note status during import")?;
+        add_tags(&tx, key, note_id, &tags)?;
+        move_note(&tx, key, note_id, &folder)?;
+        for (text, done) in checklist {
+            add_item(&tx, key, note_id, &text, done, None)?;
+        }
     }
+    let restored = if restore_attachments {
+        Some(import_attachments(&tx, key, path, &csv_signature, &note_ids)?)
+    } else {
+        None
+    };
+    rebuild_index(&tx, key)?;
     tx.commit().context("Failed to commit transaction")?;
 
     println!("Data berhasil diimpor dari '{}'", path);
+    if let Some((restored, skipped)) = restored {
+        println!("{} lampiran dipulihkan dari '{}'.", restored, attachments_file);
+        if skipped > 0 {
+            println!("{} lampiran dilewati karena catatannya tidak ada di file CSV.", skipped);
+        }
+    }
     Ok(())
 }
\ No newline at end of file
@@ -14,5 +14,121 @@ pub fn init_db() -> anyhow::Result<Connection> {
         )",
         [],
     ).context("Failed to create table")?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS metadata (
+            key TEXT PRIMARY KEY,
+            value BLOB NOT NULL
+        )",
+        [],
+    ).context("Failed to create metadata table")?;
+    add_column_if_missing(&conn, "notes", "secret", "INTEGER NOT NULL DEFAULT 0")?;
+    // Kolom `note` menjadi judul; catatan lama tetap utuh sebagai judul tanpa isi
+    add_column_if_missing(&conn, "notes", "body", "BLOB")?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS note_tokens (
+            note_id INTEGER NOT NULL,
+            token BLOB NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_note_tokens_token ON note_tokens (token);
+        CREATE INDEX IF NOT EXISTS idx_note_tokens_note_id ON note_tokens (note_id);",
+    ).context("Failed to create note_tokens table")?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS saved_searches (
+            name TEXT PRIMARY KEY,
+            mode TEXT NOT NULL,
+            query BLOB NOT NULL,
+            sort TEXT NOT NULL,
+            pinned INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    ).context("Failed to create saved_searches table")?;
+    add_column_if_missing(&conn, "saved_searches", "stemming", "INTEGER NOT NULL DEFAULT 0")?;
+    // Nama tag terenkripsi; name_hash (HMAC) dipakai untuk mencari tag yang sama
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name BLOB NOT NULL,
+            name_hash BLOB NOT NULL UNIQUE
+        );
+        CREATE TABLE IF NOT EXISTS note_tags (
+            note_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            PRIMARY KEY (note_id, tag_id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_note_tags_tag_id ON note_tags (tag_id);",
+    ).context("Failed to create tags tables")?;
+    // Setiap folder menyimpan path lengkapnya, misalnya "kerja/klien/acme"
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS folders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            path BLOB NOT NULL,
+            path_hash BLOB NOT NULL UNIQUE
+        )",
+        [],
+    ).context("Failed to create folders table")?;
+    add_column_if_missing(&conn, "notes", "folder_id", "INTEGER")?;
+    // Tenggat opsional dalam RFC 3339 (UTC), seperti createdAt dan modifiedAt
+    add_column_if_missing(&conn, "notes", "dueAt", "DATETIME")?;
+    // Catatan yang disematkan selalu tampil di atas; catatan arsip disembunyikan dari daftar dan pencarian
+    add_column_if_missing(&conn, "notes", "pinned", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(&conn, "notes", "archived", "INTEGER NOT NULL DEFAULT 0")?;
+    // Catatan di tempat sampah punya deletedAt dan dihapus permanen setelah TRASH_RETENTION_DAYS
+    add_column_if_missing(&conn, "notes", "deletedAt", "DATETIME")?;
+    // Item checklist per catatan; teks terenkripsi, urutan disimpan di `position`
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS checklist_items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            note_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            text BLOB NOT NULL,
+            done INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS idx_checklist_items_note_id ON checklist_items (note_id);",
+    ).context("Failed to create checklist_items table")?;
+    // Versi lama catatan, disalin apa adanya (terenkripsi) sebelum catatan diubah
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS note_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            note_id INTEGER NOT NULL,
+            note BLOB NOT NULL,
+            body BLOB,
+            priority BLOB NOT NULL,
+            revisedAt DATETIME NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_note_revisions_note_id ON note_revisions (note_id);",
+    ).context("Failed to create note_revisions table")?;
+    // Lampiran file; isi disimpan per potongan yang masing-masing dienkripsi dengan kunci database
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS attachments (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            note_id INTEGER NOT NULL,
+            name BLOB NOT NULL,
+            size INTEGER NOT NULL,
+            chunks INTEGER NOT NULL,
+            createdAt DATETIME NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_attachments_note_id ON attachments (note_id);
+        CREATE TABLE IF NOT EXISTS attachment_chunks (
+            attachment_id INTEGER NOT NULL,
+            seq INTEGER NOT NULL,
+            data BLOB NOT NULL,
+            PRIMARY KEY (attachment_id, seq)
+        );",
+    ).context("Failed to create attachments tables")?;
     Ok(conn)
+}
+
+// Migrasi sederhana: tambahkan kolom baru ke database lama
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> anyhow::Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))
+        .context("Failed to read table info")?;
+    let exists = stmt.query_map([], |row| row.get::<_, String>(1))
+        .context("Failed to query table info")?
+        .filter_map(Result::ok)
+        .any(|name| name == column);
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])
+            .with_context(|| format!("Failed to add column {} to {}", column, table))?;
+    }
+    Ok(())
 }
\ No newline at end of file
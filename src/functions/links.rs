@@ -0,0 +1,90 @@
+use rusqlite::Connection;
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use crate::functions::utils::{NOTE_COLUMNS, Note, SECRET_PLACEHOLDER, note_from_row};
+
+// Isi tautan di antara [[ dan ]], misalnya "12" atau "Rapat mingguan"; tautan ganda hanya diambil sekali
+pub fn parse_links(text: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("[[") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("]]") else {
+            break;
+        };
+        // Pada "[[a [[b]]" hanya "[[b]]" yang dianggap tautan
+        if let Some(nested) = rest[..end].rfind("[[") {
+            rest = &rest[nested..];
+            continue;
+        }
+        let inner = rest[..end].trim();
+        if !inner.is_empty() && !inner.contains('\n') && !links.iter().any(|l| l.eq_ignore_ascii_case(inner)) {
+            links.push(inner.to_string());
+        }
+        rest = &rest[end + 2..];
+    }
+    links
+}
+
+// Semua catatan yang bisa ditautkan (bukan di tempat sampah); catatan rahasia hanya bisa ditautkan lewat ID
+pub struct LinkIndex {
+    notes: Vec<Note>,
+}
+
+impl LinkIndex {
+    pub fn load(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<LinkIndex> {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM notes WHERE deletedAt IS NULL ORDER BY id ASC", NOTE_COLUMNS))
+            .context("Failed to prepare statement")?;
+        let notes = stmt.query_and_then([], |row| note_from_row(row, key))
+            .context("Failed to query notes for links")?
+            .collect::<anyhow::Result<Vec<Note>>>()?;
+        Ok(LinkIndex { notes })
+    }
+
+    // `[[12]]` menunjuk ID, selain itu judul (tanpa membedakan huruf besar/kecil)
+    pub fn resolve(&self, link: &str) -> Option<&Note> {
+        match link.parse::<i32>() {
+            Ok(id) => self.notes.iter().find(|n| n.id == id),
+            Err(_) => self.notes.iter().find(|n| !n.secret && n.title.trim().eq_ignore_ascii_case(link)),
+        }
+    }
+
+    // Catatan lain yang menautkan ke `note_id`; isi catatan rahasia tidak bisa dibaca sehingga tidak ikut
+    pub fn backlinks(&self, note_id: i32) -> Vec<&Note> {
+        self.notes.iter()
+            .filter(|n| n.id != note_id && !n.secret)
+            .filter(|n| parse_links(&n.text()).iter().any(|l| self.resolve(l).is_some_and(|t| t.id == note_id)))
+            .collect()
+    }
+
+    // Pasangan (catatan sumber, isi tautan) untuk setiap tautan yang tidak menunjuk catatan mana pun
+    pub fn broken_links(&self) -> Vec<(&Note, String)> {
+        self.notes.iter()
+            .filter(|n| !n.secret)
+            .flat_map(|n| parse_links(&n.text()).into_iter().map(move |l| (n, l)))
+            .filter(|(_, l)| self.resolve(l).is_none())
+            .collect()
+    }
+}
+
+pub fn link_title(note: &Note) -> &str {
+    if note.secret { SECRET_PLACEHOLDER } else { &note.title }
+}
+
+pub fn show_broken_links(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    let index = LinkIndex::load(conn, key)?;
+    let broken = index.broken_links();
+    println!("\nTautan Rusak ({}):", broken.len());
+    if broken.is_empty() {
+        println!("Semua tautan [[...]] menunjuk catatan yang ada.");
+        return Ok(());
+    }
+    println!("| {:<4} | {:<40} | {:<40} |", "ID", "Catatan", "Tautan");
+    println!("|------|------------------------------------------|------------------------------------------|");
+    for (note, link) in broken {
+        let title: String = note.title.chars().take(40).collect();
+        let link: String = format!("[[{}]]", link).chars().take(40).collect();
+        println!("| {:<4} | {:<40} | {:<40} |", note.id, title, link);
+    }
+    Ok(())
+}
@@ -0,0 +1,272 @@
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag};
+use textwrap::wrap;
+use crate::functions::highlight::use_color;
+use crate::functions::validation::env_bool;
+
+// Lebar isi catatan di tampilan detail
+const RENDER_WIDTH: usize = 80;
+const BOLD: (&str, &str) = ("\x1B[1m", "\x1B[22m");
+const ITALIC: (&str, &str) = ("\x1B[3m", "\x1B[23m");
+const STRIKE: (&str, &str) = ("\x1B[9m", "\x1B[29m");
+const CODE: (&str, &str) = ("\x1B[36m", "\x1B[39m");
+const DIM: (&str, &str) = ("\x1B[2m", "\x1B[22m");
+
+// Markdown hanya ditampilkan dan dibersihkan jika NOTE_MARKDOWN diaktifkan, selain itu isi dicetak apa adanya
+pub fn enabled() -> bool {
+    env_bool("NOTE_MARKDOWN", false)
+}
+
+fn parser(text: &str) -> Parser<'_, '_> {
+    Parser::new_ext(text, Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS)
+}
+
+// Teks polos untuk kolom tabel: penanda Markdown dibuang, satu baris per blok
+pub fn strip(text: &str) -> String {
+    let mut plain = String::new();
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    for event in parser(text) {
+        match event {
+            Event::Start(Tag::List(start)) => lists.push(start),
+            Event::End(Tag::List(_)) => {
+                lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                // Item daftar bertingkat dimulai di baris baru, bukan menempel pada teks item induk
+                if !plain.is_empty() && !plain.ends_with('\n') {
+                    plain.push('\n');
+                }
+                match lists.last_mut() {
+                    Some(Some(number)) => {
+                        plain.push_str(&format!("{}. ", number));
+                        *number += 1;
+                    }
+                    _ => plain.push_str("- "),
+                }
+            }
+            Event::Text(text) | Event::Code(text) | Event::Html(text) => plain.push_str(&text),
+            Event::TaskListMarker(done) => plain.push_str(if done { "[x] " } else { "[ ] " }),
+            Event::SoftBreak => plain.push(' '),
+            Event::HardBreak | Event::Rule => plain.push('\n'),
+            Event::End(Tag::Paragraph | Tag::Heading(..) | Tag::Item | Tag::CodeBlock(_)) => plain.push('\n'),
+            _ => {}
+        }
+    }
+    plain.lines().map(str::trim_end).filter(|l| !l.is_empty()).collect::<Vec<_>>().join("\n")
+}
+
+// Teks yang dipakai kolom tabel, sesuai pengaturan NOTE_MARKDOWN
+pub fn table_text(text: &str) -> String {
+    if enabled() { strip(text) } else { text.to_string() }
+}
+
+// Menyusun baris-baris hasil render; blok dibungkus sendiri agar indentasi daftar dan kutipan tetap rapi
+struct Renderer {
+    color: bool,
+    lines: Vec<String>,
+    inline: String,
+    quote_depth: usize,
+    lists: Vec<Option<u64>>,
+    // Indentasi isi tiap item daftar yang sedang terbuka
+    item_indents: Vec<String>,
+    // Penanda item ("- " atau "1. ") yang belum dicetak di baris pertama item
+    marker: Option<String>,
+    heading: Option<HeadingLevel>,
+}
+
+impl Renderer {
+    fn style(&mut self, codes: (&str, &str), start: bool) {
+        if self.color {
+            self.inline.push_str(if start { codes.0 } else { codes.1 });
+        }
+    }
+
+    fn quote_prefix(&self) -> String {
+        if self.color {
+            format!("{}|{} ", DIM.0, DIM.1).repeat(self.quote_depth)
+        } else {
+            "> ".repeat(self.quote_depth)
+        }
+    }
+
+    fn indent(&self) -> String {
+        self.item_indents.last().cloned().unwrap_or_default()
+    }
+
+    // Baris pertama membawa penanda item, baris berikutnya sejajar dengan isinya
+    fn first_indent(&mut self) -> String {
+        match self.marker.take() {
+            Some(marker) => {
+                let parent = self.item_indents.iter().rev().nth(1).cloned().unwrap_or_default();
+                format!("{}{}", parent, marker)
+            }
+            None => self.indent(),
+        }
+    }
+
+    fn blank_line(&mut self) {
+        if self.lists.is_empty() && self.lines.last().is_some_and(|l| !l.is_empty()) {
+            self.lines.push(String::new());
+        }
+    }
+
+    fn flush(&mut self) {
+        let text = std::mem::take(&mut self.inline);
+        if text.trim().is_empty() {
+            return;
+        }
+        let quote = self.quote_prefix();
+        let first = format!("{}{}", quote, self.first_indent());
+        let rest = format!("{}{}", quote, self.indent());
+        let options = textwrap::Options::new(RENDER_WIDTH).initial_indent(&first).subsequent_indent(&rest);
+        self.lines.extend(wrap(text.trim(), options).into_iter().map(|l| l.into_owned()));
+    }
+
+    fn end_heading(&mut self, level: HeadingLevel) {
+        let title = std::mem::take(&mut self.inline);
+        let title = title.trim();
+        let width = textwrap::core::display_width(title);
+        if self.color {
+            self.lines.push(format!("{}{}{}", BOLD.0, title, BOLD.1));
+        } else {
+            self.lines.push(title.to_string());
+        }
+        match level {
+            HeadingLevel::H1 => self.lines.push("=".repeat(width)),
+            HeadingLevel::H2 => self.lines.push("-".repeat(width)),
+            _ => {}
+        }
+    }
+
+    fn end_code_block(&mut self) {
+        let code = std::mem::take(&mut self.inline);
+        let prefix = format!("{}{}    ", self.quote_prefix(), self.indent());
+        for line in code.trim_end_matches('\n').lines() {
+            if self.color {
+                self.lines.push(format!("{}{}{}{}", prefix, CODE.0, line, CODE.1));
+            } else {
+                self.lines.push(format!("{}{}", prefix, line));
+            }
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(Tag::Paragraph) => {}
+            Event::End(Tag::Paragraph) => {
+                self.flush();
+                self.blank_line();
+            }
+            Event::Start(Tag::Heading(level, ..)) => {
+                self.flush();
+                self.heading = Some(level);
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some(level) = self.heading.take() {
+                    self.end_heading(level);
+                }
+                self.blank_line();
+            }
+            Event::Start(Tag::BlockQuote) => {
+                self.flush();
+                self.quote_depth += 1;
+            }
+            Event::End(Tag::BlockQuote) => {
+                self.flush();
+                self.quote_depth -= 1;
+                self.blank_line();
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                self.flush();
+                if let CodeBlockKind::Fenced(lang) = kind
+                    && !lang.is_empty()
+                {
+                    let label = format!("{}{}[{}]", self.quote_prefix(), self.indent(), lang);
+                    self.lines.push(if self.color { format!("{}{}{}", DIM.0, label, DIM.1) } else { label });
+                }
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                self.end_code_block();
+                self.blank_line();
+            }
+            Event::Start(Tag::List(start)) => {
+                // Teks item induk dicetak dulu sebelum daftar bertingkat
+                self.flush();
+                self.lists.push(start);
+            }
+            Event::End(Tag::List(_)) => {
+                self.lists.pop();
+                self.blank_line();
+            }
+            Event::Start(Tag::Item) => {
+                self.flush();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        let marker = format!("{}. ", number);
+                        *number += 1;
+                        marker
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.item_indents.push(format!("{}{}", self.indent(), " ".repeat(marker.len())));
+                self.marker = Some(marker);
+            }
+            Event::End(Tag::Item) => {
+                self.flush();
+                self.item_indents.pop();
+                self.marker = None;
+            }
+            Event::Start(Tag::Emphasis) => self.style(ITALIC, true),
+            Event::End(Tag::Emphasis) => self.style(ITALIC, false),
+            Event::Start(Tag::Strong) => self.style(BOLD, true),
+            Event::End(Tag::Strong) => self.style(BOLD, false),
+            Event::Start(Tag::Strikethrough) => self.style(STRIKE, true),
+            Event::End(Tag::Strikethrough) => self.style(STRIKE, false),
+            // Alamat tautan ditampilkan setelah teksnya
+            Event::End(Tag::Link(_, url, _)) | Event::End(Tag::Image(_, url, _))
+                if !self.inline.ends_with(url.as_ref()) =>
+            {
+                self.inline.push_str(&format!(" <{}>", url));
+            }
+            Event::Text(text) => self.inline.push_str(&text),
+            Event::Code(code) => {
+                if self.color {
+                    self.inline.push_str(&format!("{}{}{}", CODE.0, code, CODE.1));
+                } else {
+                    self.inline.push_str(&format!("`{}`", code));
+                }
+            }
+            Event::Html(html) => self.inline.push_str(&html),
+            Event::TaskListMarker(done) => self.inline.push_str(if done { "[x] " } else { "[ ] " }),
+            Event::SoftBreak => self.inline.push(' '),
+            Event::HardBreak => self.inline.push('\n'),
+            Event::Rule => {
+                self.flush();
+                self.lines.push("-".repeat(40));
+                self.blank_line();
+            }
+            _ => {}
+        }
+    }
+}
+
+// Isi catatan untuk tampilan detail; gaya ANSI hanya dipakai jika output ke terminal
+pub fn render(text: &str) -> String {
+    let mut renderer = Renderer {
+        color: use_color(),
+        lines: Vec::new(),
+        inline: String::new(),
+        quote_depth: 0,
+        lists: Vec::new(),
+        item_indents: Vec::new(),
+        marker: None,
+        heading: None,
+    };
+    for event in parser(text) {
+        renderer.event(event);
+    }
+    renderer.flush();
+    while renderer.lines.last().is_some_and(|l| l.is_empty()) {
+        renderer.lines.pop();
+    }
+    renderer.lines.join("\n")
+}
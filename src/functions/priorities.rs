@@ -0,0 +1,412 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use std::collections::HashMap;
+use crate::functions::highlight::use_color;
+use crate::functions::query_filter::rename_priority;
+use crate::functions::search_notes::{regex_query, split_regex_query};
+use crate::functions::utils::{decrypt_data, encrypt_data, read_input};
+use crate::functions::validation::{print_errors, validate_priority_name};
+
+const METADATA_KEY: &str = "priority_levels";
+const COLOR_END: &str = "\x1B[0m";
+const COLORS: [(&str, &str); 6] = [
+    ("merah", "\x1B[31m"),
+    ("hijau", "\x1B[32m"),
+    ("kuning", "\x1B[33m"),
+    ("biru", "\x1B[34m"),
+    ("magenta", "\x1B[35m"),
+    ("cyan", "\x1B[36m"),
+];
+
+pub struct PriorityLevel {
+    pub name: String,
+    // Salah satu nama di COLORS, None berarti tanpa warna
+    pub color: Option<String>,
+}
+
+// Level prioritas per database, urut dari yang paling penting
+pub struct Priorities {
+    pub levels: Vec<PriorityLevel>,
+    pub default: usize,
+}
+
+impl Default for Priorities {
+    fn default() -> Self {
+        let level = |name: &str, color: &str| PriorityLevel { name: name.to_string(), color: Some(color.to_string()) };
+        Priorities {
+            levels: vec![level("Tinggi", "merah"), level("Sedang", "kuning"), level("Rendah", "hijau")],
+            default: 1,
+        }
+    }
+}
+
+impl Priorities {
+    // Disimpan terenkripsi di tabel metadata, satu level per baris: nama, warna, penanda default
+    pub fn load(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<Priorities> {
+        let stored: Option<Vec<u8>> = conn.query_row(
+            "SELECT value FROM metadata WHERE key = ?1",
+            params![METADATA_KEY],
+            |row| row.get(0),
+        ).optional().context("Failed to read priority levels")?;
+        let Some(stored) = stored else {
+            return Ok(Priorities::default());
+        };
+        let text = decrypt_data(&stored, key).context("Failed to decrypt priority levels")?;
+        let mut priorities = Priorities { levels: Vec::new(), default: 0 };
+        for line in text.lines() {
+            let mut fields = line.split('\t');
+            let name = fields.next().unwrap_or_default().to_string();
+            let color = fields.next().filter(|c| !c.is_empty()).map(str::to_string);
+            if fields.next() == Some("1") {
+                priorities.default = priorities.levels.len();
+            }
+            priorities.levels.push(PriorityLevel { name, color });
+        }
+        if priorities.levels.is_empty() {
+            return Ok(Priorities::default());
+        }
+        Ok(priorities)
+    }
+
+    pub fn save(&self, conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+        let text = self.levels.iter().enumerate()
+            .map(|(i, level)| format!(
+                "{}\t{}\t{}", level.name, level.color.as_deref().unwrap_or_default(), u8::from(i == self.default)
+            ))
+            .collect::<Vec<_>>()
+            .join("\n");
+        conn.execute(
+            "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
+            params![METADATA_KEY, encrypt_data(&text, key)?],
+        ).context("Failed to store priority levels")?;
+        Ok(())
+    }
+
+    pub fn default_name(&self) -> &str {
+        &self.levels[self.default].name
+    }
+
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.levels.iter().position(|l| l.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    // Urutan untuk pengurutan; prioritas yang tidak dikenal ditaruh paling akhir
+    pub fn rank(&self, name: &str) -> usize {
+        self.position(name).unwrap_or(self.levels.len())
+    }
+
+    // Pilihan berupa nomor (mulai dari 1) atau nama level
+    pub fn find(&self, choice: &str) -> Option<&str> {
+        let index = match choice.trim().parse::<usize>() {
+            Ok(number) => number.checked_sub(1).filter(|i| *i < self.levels.len()),
+            Err(_) => self.position(choice),
+        };
+        index.map(|i| self.levels[i].name.as_str())
+    }
+
+    // Contoh: "1: Tinggi, 2: Sedang, 3: Rendah"
+    pub fn options(&self) -> String {
+        self.levels.iter().enumerate()
+            .map(|(i, level)| format!("{}: {}", i + 1, level.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // Nama prioritas dengan padding, diberi warna level jika output ke terminal
+    pub fn label(&self, name: &str, width: usize) -> String {
+        let padded = format!("{:<width$}", name);
+        let color = self.position(name)
+            .and_then(|i| self.levels[i].color.as_deref())
+            .and_then(color_code);
+        match color {
+            Some(code) if use_color() => format!("{}{}{}", code, padded, COLOR_END),
+            _ => padded,
+        }
+    }
+}
+
+fn color_code(name: &str) -> Option<&'static str> {
+    COLORS.iter().find(|(n, _)| *n == name).map(|(_, code)| *code)
+}
+
+// Prioritas yang dipakai catatan beserta jumlahnya, termasuk yang ada di tempat sampah
+fn priority_counts(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<HashMap<String, usize>> {
+    let mut stmt = conn.prepare("SELECT priority FROM notes").context("Failed to prepare statement")?;
+    let rows = stmt.query_map([], |row| row.get::<_, Vec<u8>>(0)).context("Failed to query priorities")?;
+    let mut counts = HashMap::new();
+    for encrypted in rows {
+        let priority = decrypt_data(&encrypted?, key).context("Failed to decrypt priority")?;
+        *counts.entry(priority).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+// Mengganti prioritas `from` menjadi `to` di catatan dan riwayat revisinya; nilai terenkripsi harus dibuka satu per satu
+fn remap(conn: &Connection, key: &Key<Aes256Gcm>, from: &str, to: &str) -> anyhow::Result<usize> {
+    let mut notes = 0;
+    for table in ["notes", "note_revisions"] {
+        let mut stmt = conn.prepare(&format!("SELECT id, priority FROM {}", table))
+            .context("Failed to prepare statement")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?)))
+            .context("Failed to query priorities")?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (id, encrypted) in rows {
+            if !decrypt_data(&encrypted, key)?.eq_ignore_ascii_case(from) {
+                continue;
+            }
+            conn.execute(
+                &format!("UPDATE {} SET priority = ?1 WHERE id = ?2", table),
+                params![encrypt_data(to, key)?, id],
+            ).context("Failed to update priority")?;
+            if table == "notes" {
+                notes += 1;
+            }
+        }
+    }
+    Ok(notes)
+}
+
+// Mengganti prio:<from> di pencarian tersimpan; nama dengan spasi atau koma tidak bisa ditulis di query,
+// jadi pencarian itu hanya dilaporkan agar diperbaiki sendiri
+fn remap_saved_searches(conn: &Connection, key: &Key<Aes256Gcm>, from: &str, to: &str) -> anyhow::Result<(usize, Vec<String>)> {
+    let mut stmt = conn.prepare("SELECT name, mode, query FROM saved_searches").context("Failed to prepare statement")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Vec<u8>>(2)?)))
+        .context("Failed to query saved searches")?
+        .collect::<Result<Vec<_>, _>>()?;
+    let expressible = !to.contains(|c: char| c.is_whitespace() || c == ',');
+    let mut updated = 0;
+    let mut stale = Vec::new();
+    for (name, mode, encrypted) in rows {
+        let query = decrypt_data(&encrypted, key).context("Failed to decrypt query")?;
+        // Pola regex tidak diubah, hanya bagian filternya
+        let renamed = if mode == "4" {
+            let (pattern, filters) = split_regex_query(&query);
+            rename_priority(filters, from, to).map(|filters| regex_query(pattern, &filters))
+        } else {
+            rename_priority(&query, from, to)
+        };
+        let Some(renamed) = renamed else {
+            continue;
+        };
+        if !expressible {
+            stale.push(name);
+            continue;
+        }
+        conn.execute(
+            "UPDATE saved_searches SET query = ?1 WHERE name = ?2",
+            params![encrypt_data(&renamed, key)?, name],
+        ).context("Failed to update saved search")?;
+        updated += 1;
+    }
+    Ok((updated, stale))
+}
+
+fn print_search_changes(to: &str, (updated, stale): (usize, Vec<String>)) {
+    if updated > 0 {
+        println!("{} pencarian tersimpan diperbarui.", updated);
+    }
+    if !stale.is_empty() {
+        println!(
+            "PERINGATAN: '{}' tidak bisa dipakai di filter prio:, perbarui pencarian tersimpan ini secara manual: {}",
+            to,
+            stale.join(", ")
+        );
+    }
+}
+
+fn print_levels(priorities: &Priorities, counts: &HashMap<String, usize>) {
+    println!("\nLevel Prioritas (urut dari yang paling penting):");
+    println!("| {:<4} | {:<10} | {:<8} | {:<7} | {:<7} |", "No", "Nama", "Warna", "Catatan", "Default");
+    println!("|------|------------|----------|---------|---------|");
+    for (i, level) in priorities.levels.iter().enumerate() {
+        println!(
+            "| {:<4} | {} | {:<8} | {:<7} | {:<7} |",
+            i + 1,
+            priorities.label(&level.name, 10),
+            level.color.as_deref().unwrap_or("-"),
+            counts.get(&level.name).copied().unwrap_or(0),
+            if i == priorities.default { "ya" } else { "" },
+        );
+    }
+    let unknown: Vec<String> = counts.iter()
+        .filter(|(name, _)| priorities.position(name).is_none())
+        .map(|(name, count)| format!("{} ({})", name, count))
+        .collect();
+    if !unknown.is_empty() {
+        println!("Prioritas tanpa level: {}", unknown.join(", "));
+    }
+}
+
+fn read_level(priorities: &Priorities, prompt: &str) -> anyhow::Result<Option<usize>> {
+    println!("{} ({}): ", prompt, priorities.options());
+    let mut input = String::new();
+    read_input(&mut input)?;
+    let index = priorities.find(&input).and_then(|name| priorities.position(name));
+    if index.is_none() {
+        println!("Level prioritas tidak valid!");
+    }
+    Ok(index)
+}
+
+fn read_name(priorities: &Priorities, prompt: &str) -> anyhow::Result<Option<String>> {
+    println!("{}", prompt);
+    let mut input = String::new();
+    read_input(&mut input)?;
+    match validate_priority_name(&input) {
+        Ok(name) if priorities.position(&name).is_some() => {
+            println!("Level '{}' sudah ada!", name);
+            Ok(None)
+        }
+        Ok(name) => Ok(Some(name)),
+        Err(e) => {
+            print_errors(&[e]);
+            Ok(None)
+        }
+    }
+}
+
+fn read_color() -> anyhow::Result<Option<Option<String>>> {
+    let names: Vec<&str> = COLORS.iter().map(|(n, _)| *n).collect();
+    println!("Warna ({}, kosongkan untuk tanpa warna): ", names.join(", "));
+    let mut input = String::new();
+    read_input(&mut input)?;
+    let color = input.trim().to_lowercase();
+    if color.is_empty() {
+        return Ok(Some(None));
+    }
+    if color_code(&color).is_none() {
+        println!("Warna tidak dikenal!");
+        return Ok(None);
+    }
+    Ok(Some(Some(color)))
+}
+
+pub fn priorities_menu(conn: &mut Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    loop {
+        let mut priorities = Priorities::load(conn, key)?;
+        let counts = priority_counts(conn, key)?;
+        print_levels(&priorities, &counts);
+        println!("\nMenu Prioritas:");
+        println!("1. Tambah Level");
+        println!("2. Ubah Nama Level");
+        println!("3. Ubah Warna Level");
+        println!("4. Pindahkan Level");
+        println!("5. Jadikan Default");
+        println!("6. Hapus Level");
+        println!("7. Petakan Prioritas Tanpa Level");
+        println!("8. Kembali ke Menu Utama");
+        println!("Pilih opsi (1-8): ");
+
+        let mut choice = String::new();
+        read_input(&mut choice)?;
+        let choice: i32 = choice.trim().parse().unwrap_or(0);
+
+        // Perubahan level dan pemetaan catatan disimpan bersama
+        let tx = conn.transaction().context("Failed to start transaction")?;
+        match choice {
+            1 => {
+                let Some(name) = read_name(&priorities, "Nama level baru: ")? else {
+                    continue;
+                };
+                let Some(color) = read_color()? else {
+                    continue;
+                };
+                println!("Level '{}' ditambahkan.", name);
+                priorities.levels.push(PriorityLevel { name, color });
+            }
+            2 => {
+                let Some(index) = read_level(&priorities, "Level yang akan diubah namanya")? else {
+                    continue;
+                };
+                let Some(name) = read_name(&priorities, "Nama baru: ")? else {
+                    continue;
+                };
+                let old = std::mem::replace(&mut priorities.levels[index].name, name.clone());
+                let count = remap(&tx, key, &old, &name)?;
+                let searches = remap_saved_searches(&tx, key, &old, &name)?;
+                println!("Level '{}' menjadi '{}', {} catatan diperbarui.", old, name, count);
+                print_search_changes(&name, searches);
+            }
+            3 => {
+                let Some(index) = read_level(&priorities, "Level yang akan diubah warnanya")? else {
+                    continue;
+                };
+                let Some(color) = read_color()? else {
+                    continue;
+                };
+                priorities.levels[index].color = color;
+            }
+            4 => {
+                let Some(from) = read_level(&priorities, "Level yang akan dipindahkan")? else {
+                    continue;
+                };
+                println!("Pindahkan ke urutan ke- (1-{}): ", priorities.levels.len());
+                let mut to = String::new();
+                read_input(&mut to)?;
+                let Some(to) = to.trim().parse::<usize>().ok().filter(|n| (1..=priorities.levels.len()).contains(n)) else {
+                    println!("Urutan tidak valid!");
+                    continue;
+                };
+                let default = priorities.default_name().to_string();
+                let level = priorities.levels.remove(from);
+                priorities.levels.insert(to - 1, level);
+                priorities.default = priorities.position(&default).unwrap_or(0);
+            }
+            5 => {
+                let Some(index) = read_level(&priorities, "Level default untuk catatan baru")? else {
+                    continue;
+                };
+                priorities.default = index;
+            }
+            6 => {
+                if priorities.levels.len() == 1 {
+                    println!("Level terakhir tidak bisa dihapus.");
+                    continue;
+                }
+                let Some(index) = read_level(&priorities, "Level yang akan dihapus")? else {
+                    continue;
+                };
+                let removed = priorities.levels[index].name.clone();
+                let Some(target) = read_level(&priorities, &format!("Pindahkan catatan '{}' ke level", removed))? else {
+                    continue;
+                };
+                if target == index {
+                    println!("Level tujuan harus berbeda dari level yang dihapus!");
+                    continue;
+                }
+                let target_name = priorities.levels[target].name.clone();
+                let count = remap(&tx, key, &removed, &target_name)?;
+                let searches = remap_saved_searches(&tx, key, &removed, &target_name)?;
+                let default = if index == priorities.default { target_name.clone() } else { priorities.default_name().to_string() };
+                priorities.levels.remove(index);
+                priorities.default = priorities.position(&default).unwrap_or(0);
+                println!("Level '{}' dihapus, {} catatan dipindahkan ke '{}'.", removed, count, target_name);
+                print_search_changes(&target_name, searches);
+            }
+            7 => {
+                let unknown: Vec<&String> = counts.keys().filter(|name| priorities.position(name).is_none()).collect();
+                if unknown.is_empty() {
+                    println!("Semua catatan sudah memakai level yang ada.");
+                    continue;
+                }
+                for name in unknown {
+                    let Some(target) = read_level(&priorities, &format!("Petakan '{}' ({} catatan) ke level", name, counts[name]))? else {
+                        continue;
+                    };
+                    let count = remap(&tx, key, name, &priorities.levels[target].name)?;
+                    let searches = remap_saved_searches(&tx, key, name, &priorities.levels[target].name)?;
+                    println!("{} catatan '{}' dipetakan ke '{}'.", count, name, priorities.levels[target].name);
+                    print_search_changes(&priorities.levels[target].name, searches);
+                }
+            }
+            8 => return Ok(()),
+            _ => {
+                println!("Pilihan tidak valid!");
+                continue;
+            }
+        }
+        priorities.save(&tx, key)?;
+        tx.commit().context("Failed to commit transaction")?;
+    }
+}
@@ -0,0 +1,352 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::cmp::Ordering;
+use crate::functions::blind_index::normalize;
+use crate::functions::folders::in_folder;
+use crate::functions::tags::normalize_tag;
+use crate::functions::validation::validate_folder;
+use crate::functions::utils::Note;
+
+#[derive(Debug, Clone, Copy)]
+enum Comparison {
+    Less,
+    LessEqual,
+    Equal,
+    GreaterEqual,
+    Greater,
+}
+
+impl Comparison {
+    fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Less => ordering == Ordering::Less,
+            Comparison::LessEqual => ordering != Ordering::Greater,
+            Comparison::Equal => ordering == Ordering::Equal,
+            Comparison::GreaterEqual => ordering != Ordering::Less,
+            Comparison::Greater => ordering == Ordering::Greater,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum DateValue {
+    // Tanggal absolut, dibandingkan per hari
+    Day(NaiveDate),
+    // Umur catatan, misalnya 7d berarti tujuh hari yang lalu
+    Age(Duration),
+}
+
+// Status checklist untuk filter `todo:`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ChecklistState {
+    // Masih ada item yang belum selesai
+    Open,
+    // Punya checklist dan semua item sudah selesai
+    Done,
+}
+
+#[derive(Debug, Clone)]
+struct DateCondition {
+    comparison: Comparison,
+    value: DateValue,
+}
+
+impl DateCondition {
+    fn matches(&self, dt: DateTime<Utc>) -> bool {
+        match &self.value {
+            DateValue::Day(day) => self.comparison.accepts(dt.date_naive().cmp(day)),
+            // signed_duration_since tidak panik untuk tanggal ekstrem, selisih dua DateTime selalu muat di Duration
+            DateValue::Age(age) => self.comparison.accepts(Utc::now().signed_duration_since(dt).cmp(age)),
+        }
+    }
+}
+
+// Hasil parsing query seperti `deploy prio:tinggi tag:kerja folder:kerja/klien todo:open is:pinned created:>=2025-03-01 modified:<7d`
+#[derive(Debug, Clone, Default)]
+pub struct NoteFilter {
+    pub text: String,
+    priorities: Vec<String>,
+    // Setiap `tag:` adalah satu grup; catatan cocok jika punya salah satu tag di setiap grup
+    tags: Vec<Vec<String>>,
+    // Folder beserta semua subfoldernya
+    folders: Vec<String>,
+    checklist: Option<ChecklistState>,
+    // Tanpa `is:archived`, catatan arsip tidak pernah cocok
+    archived: bool,
+    pinned: bool,
+    created: Vec<DateCondition>,
+    modified: Vec<DateCondition>,
+}
+
+impl NoteFilter {
+    pub fn has_fields(&self) -> bool {
+        !self.priorities.is_empty() || !self.tags.is_empty() || !self.folders.is_empty() || self.checklist.is_some()
+            || self.archived || self.pinned
+            || !self.created.is_empty() || !self.modified.is_empty()
+    }
+
+    // Hanya filter field; bagian teks ditangani oleh mode pencarian masing-masing
+    pub fn matches_fields(&self, note: &Note) -> bool {
+        let priority_ok = self.priorities.is_empty()
+            || self.priorities.iter().any(|p| p.eq_ignore_ascii_case(&note.priority));
+        let tags_ok = self.tags.iter().all(|group| {
+            group.iter().any(|tag| note.tags.iter().any(|t| normalize_tag(t) == *tag))
+        });
+        let (done, total) = note.checklist;
+        let checklist_ok = match self.checklist {
+            Some(ChecklistState::Open) => done < total,
+            Some(ChecklistState::Done) => total > 0 && done == total,
+            None => true,
+        };
+        let modified_at = note.modified_at.unwrap_or(note.created_at);
+        note.archived == self.archived
+            && (!self.pinned || note.pinned)
+            && priority_ok
+            && checklist_ok
+            && tags_ok
+            && self.folders.iter().all(|scope| in_folder(&note.folder, scope))
+            && self.created.iter().all(|c| c.matches(note.created_at))
+            && self.modified.iter().all(|c| c.matches(modified_at))
+    }
+
+    // Filter field ditambah semua kata teks harus ada di isi catatan
+    pub fn matches(&self, note: &Note) -> bool {
+        if !self.matches_fields(note) {
+            return false;
+        }
+        if self.text.is_empty() {
+            return true;
+        }
+        if note.secret {
+            return false;
+        }
+        let haystack = normalize(&note.text());
+        self.text.split_whitespace().all(|term| haystack.contains(&normalize(term)))
+    }
+}
+
+fn parse_date_condition(raw: &str) -> Result<DateCondition, String> {
+    let (comparison, value) = [
+        (">=", Comparison::GreaterEqual),
+        ("<=", Comparison::LessEqual),
+        (">", Comparison::Greater),
+        ("<", Comparison::Less),
+        ("=", Comparison::Equal),
+    ]
+    .iter()
+    .find_map(|(prefix, comparison)| raw.strip_prefix(prefix).map(|rest| (*comparison, rest)))
+    .unwrap_or((Comparison::Equal, raw));
+
+    if let Ok(day) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(DateCondition { comparison, value: DateValue::Day(day) });
+    }
+
+    let invalid = || format!("Tanggal '{}' tidak valid, gunakan YYYY-MM-DD atau angka dengan h/d/w (mis. 7d)", value);
+    let unit_start = value.char_indices().last().map_or(0, |(i, _)| i);
+    let (number, unit) = value.split_at(unit_start);
+    let amount: i64 = number.parse().map_err(|_| invalid())?;
+    // Angka yang terlalu besar untuk rentang durasi ditolak, bukan membuat program panik
+    let age = match unit {
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => return Err(invalid()),
+    }
+    .ok_or_else(invalid)?;
+    Ok(DateCondition { comparison, value: DateValue::Age(age) })
+}
+
+pub fn parse_filter(input: &str) -> Result<NoteFilter, String> {
+    let mut filter = NoteFilter::default();
+    let mut text = Vec::new();
+    for part in input.split_whitespace() {
+        let Some((field, value)) = part.split_once(':') else {
+            text.push(part);
+            continue;
+        };
+        match field.to_lowercase().as_str() {
+            "prio" | "priority" | "prioritas" => filter.priorities.extend(
+                value.split(',').filter(|p| !p.is_empty()).map(str::to_string),
+            ),
+            "tag" => filter.tags.push(
+                value.split(',').map(normalize_tag).filter(|t| !t.is_empty()).collect(),
+            ),
+            "folder" => filter.folders.push(validate_folder(value).map_err(|e| e.to_string())?),
+            "todo" => filter.checklist = Some(match value.to_lowercase().as_str() {
+                "open" | "terbuka" => ChecklistState::Open,
+                "done" | "selesai" => ChecklistState::Done,
+                _ => return Err(format!("Nilai todo '{}' tidak valid, gunakan todo:open atau todo:done", value)),
+            }),
+            "is" => match value.to_lowercase().as_str() {
+                "archived" | "arsip" => filter.archived = true,
+                "pinned" | "disematkan" => filter.pinned = true,
+                _ => return Err(format!("Nilai is '{}' tidak valid, gunakan is:archived atau is:pinned", value)),
+            },
+            "created" | "dibuat" => filter.created.push(parse_date_condition(value)?),
+            "modified" | "diperbarui" => filter.modified.push(parse_date_condition(value)?),
+            _ => text.push(part),
+        }
+    }
+    filter.text = text.join(" ");
+    Ok(filter)
+}
+
+// Mengganti nilai prio:<from> menjadi <to> di query filter, termasuk di daftar seperti prio:a,b; None jika tidak ada yang berubah
+pub fn rename_priority(query: &str, from: &str, to: &str) -> Option<String> {
+    let mut changed = false;
+    let mut renamed = String::new();
+    // Spasi asli dipertahankan agar query tersimpan hanya berubah di nilai prio:
+    for segment in query.split_inclusive(char::is_whitespace) {
+        let token = segment.trim_end_matches(char::is_whitespace);
+        match token.split_once(':') {
+            Some((field, value)) if matches!(field.to_lowercase().as_str(), "prio" | "priority" | "prioritas") => {
+                let values: Vec<&str> = value.split(',')
+                    .map(|p| if p.eq_ignore_ascii_case(from) { changed = true; to } else { p })
+                    .collect();
+                renamed.push_str(&format!("{}:{}", field, values.join(",")));
+            }
+            _ => renamed.push_str(token),
+        }
+        renamed.push_str(&segment[token.len()..]);
+    }
+    changed.then_some(renamed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(priority: &str, created_at: DateTime<Utc>) -> Note {
+        Note {
+            id: 1,
+            title: "Rapat tim".to_string(),
+            body: "Bahas laporan pajak".to_string(),
+            priority: priority.to_string(),
+            created_at,
+            modified_at: None,
+            secret: false,
+            tags: vec!["Kerja".to_string()],
+            folder: "kerja/klien".to_string(),
+            due_at: None,
+            checklist: (1, 2),
+            pinned: false,
+            archived: false,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn fields_are_taken_out_of_the_text() {
+        let filter = parse_filter("deploy  prio:tinggi tag:kerja,#rumah folder:kerja server").unwrap();
+        assert_eq!(filter.text, "deploy server");
+        assert_eq!(filter.priorities, vec!["tinggi"]);
+        assert_eq!(filter.tags, vec![vec!["kerja".to_string(), "rumah".to_string()]]);
+        assert_eq!(filter.folders, vec!["kerja"]);
+        assert!(filter.has_fields());
+        assert!(!parse_filter("deploy server").unwrap().has_fields());
+    }
+
+    #[test]
+    fn unknown_fields_stay_in_the_text() {
+        let filter = parse_filter("https://example.com ticket:42").unwrap();
+        assert_eq!(filter.text, "https://example.com ticket:42");
+        assert!(!filter.has_fields());
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        assert!(parse_filter("todo:kadang").is_err());
+        assert!(parse_filter("is:hilang").is_err());
+        assert!(parse_filter("created:kemarin").is_err());
+        assert!(parse_filter("created:7x").is_err());
+        assert!(parse_filter("modified:<d").is_err());
+    }
+
+    #[test]
+    fn date_conditions_parse_comparison_and_value() {
+        let condition = parse_date_condition(">=2025-03-01").unwrap();
+        assert!(matches!(condition.comparison, Comparison::GreaterEqual));
+        assert!(matches!(condition.value, DateValue::Day(day) if day == NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()));
+
+        let condition = parse_date_condition("<7d").unwrap();
+        assert!(matches!(condition.comparison, Comparison::Less));
+        assert!(matches!(condition.value, DateValue::Age(age) if age == Duration::days(7)));
+
+        assert!(matches!(parse_date_condition("2w").unwrap().comparison, Comparison::Equal));
+        assert!(matches!(parse_date_condition("12h").unwrap().value, DateValue::Age(age) if age == Duration::hours(12)));
+    }
+
+    #[test]
+    fn ages_outside_the_duration_range_are_rejected() {
+        assert!(parse_date_condition("<99999999999999d").is_err());
+        assert!(parse_date_condition("9223372036854775807h").is_err());
+        assert!(parse_date_condition(">99999999999999w").is_err());
+        assert!(parse_filter("foo created:<99999999999999d").is_err());
+    }
+
+    #[test]
+    fn date_conditions_handle_extreme_dates() {
+        let condition = parse_date_condition("<7d").unwrap();
+        assert!(!condition.matches(DateTime::<Utc>::MIN_UTC));
+        assert!(condition.matches(DateTime::<Utc>::MAX_UTC));
+        let condition = parse_date_condition(">1d").unwrap();
+        assert!(condition.matches(DateTime::<Utc>::MIN_UTC));
+    }
+
+    #[test]
+    fn matches_fields_applies_every_filter() {
+        let now = Utc::now();
+        let note = note("Tinggi", now - Duration::days(3));
+        assert!(parse_filter("prio:tinggi").unwrap().matches_fields(&note));
+        assert!(parse_filter("prio:rendah,TINGGI").unwrap().matches_fields(&note));
+        assert!(!parse_filter("prio:rendah").unwrap().matches_fields(&note));
+        assert!(parse_filter("tag:kerja folder:kerja").unwrap().matches_fields(&note));
+        assert!(!parse_filter("tag:kerja tag:rumah").unwrap().matches_fields(&note));
+        assert!(!parse_filter("folder:ker").unwrap().matches_fields(&note));
+        assert!(parse_filter("todo:open").unwrap().matches_fields(&note));
+        assert!(!parse_filter("todo:done").unwrap().matches_fields(&note));
+        assert!(parse_filter("created:<7d").unwrap().matches_fields(&note));
+        assert!(!parse_filter("created:<1d").unwrap().matches_fields(&note));
+        assert!(!parse_filter("is:pinned").unwrap().matches_fields(&note));
+    }
+
+    #[test]
+    fn archived_notes_need_is_archived() {
+        let mut note = note("Tinggi", Utc::now());
+        note.archived = true;
+        assert!(!parse_filter("").unwrap().matches_fields(&note));
+        assert!(parse_filter("is:archived").unwrap().matches_fields(&note));
+    }
+
+    #[test]
+    fn text_terms_must_all_appear() {
+        let note = note("Tinggi", Utc::now());
+        assert!(parse_filter("rapat pajak prio:tinggi").unwrap().matches(&note));
+        assert!(!parse_filter("rapat direksi").unwrap().matches(&note));
+    }
+
+    #[test]
+    fn rename_priority_rewrites_prio_values_case_insensitively() {
+        assert_eq!(rename_priority("rapat prio:tinggi", "Tinggi", "Penting").as_deref(), Some("rapat prio:Penting"));
+        assert_eq!(rename_priority("Priority:TINGGI", "tinggi", "Penting").as_deref(), Some("Priority:Penting"));
+        assert_eq!(rename_priority("prioritas:tinggi", "Tinggi", "Penting").as_deref(), Some("prioritas:Penting"));
+    }
+
+    #[test]
+    fn rename_priority_updates_lists_and_keeps_other_values() {
+        assert_eq!(
+            rename_priority("prio:rendah,tinggi,,sedang", "Tinggi", "Penting").as_deref(),
+            Some("prio:rendah,Penting,,sedang")
+        );
+        assert_eq!(rename_priority("prio:tinggian", "Tinggi", "Penting"), None);
+    }
+
+    #[test]
+    fn rename_priority_leaves_the_rest_of_the_query_untouched() {
+        assert_eq!(
+            rename_priority("  tinggi\tprio:tinggi  tag:tinggi ", "Tinggi", "Penting").as_deref(),
+            Some("  tinggi\tprio:Penting  tag:tinggi ")
+        );
+        assert_eq!(rename_priority("tinggi tag:tinggi", "Tinggi", "Penting"), None);
+        assert_eq!(rename_priority("", "Tinggi", "Penting"), None);
+    }
+}
@@ -0,0 +1,310 @@
+use rusqlite::{Connection, params};
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use crate::functions::blind_index::index_note;
+use crate::functions::highlight::use_color;
+use crate::functions::utils::{
+    NOTE_COLUMNS, decrypt_data, decrypt_with_passphrase, full_text, note_from_row, read_input, read_passphrase,
+};
+
+const REMOVED_START: &str = "\x1B[31m";
+const ADDED_START: &str = "\x1B[32m";
+const COLOR_END: &str = "\x1B[0m";
+
+// Satu versi catatan; revisi disimpan persis seperti kolom di tabel notes (tetap terenkripsi)
+struct Version {
+    // None untuk versi yang sekarang
+    revision_id: Option<i64>,
+    title: String,
+    body: String,
+    priority: String,
+    at: DateTime<Utc>,
+}
+
+impl Version {
+    fn text(&self) -> String {
+        full_text(&self.title, &self.body)
+    }
+}
+
+// Menyimpan isi catatan saat ini sebagai revisi, dipanggil sebelum catatan diubah
+pub fn save_revision(conn: &Connection, note_id: i32) -> anyhow::Result<()> {
+    conn.execute(
+        "INSERT INTO note_revisions (note_id, note, body, priority, revisedAt)
+         SELECT id, note, body, priority, COALESCE(modifiedAt, createdAt) FROM notes WHERE id = ?1",
+        params![note_id],
+    ).context("Failed to save revision")?;
+    Ok(())
+}
+
+// Dipanggil saat catatan dihapus permanen
+pub fn remove_note_revisions(conn: &Connection, note_id: i32) -> anyhow::Result<()> {
+    conn.execute("DELETE FROM note_revisions WHERE note_id = ?1", params![note_id])
+        .context("Failed to delete revisions")?;
+    Ok(())
+}
+
+// Judul dan isi catatan rahasia dibuka dengan passphrase; isi kosong tidak disegel
+fn unseal(text: String, passphrase: Option<&str>) -> anyhow::Result<String> {
+    match passphrase {
+        Some(passphrase) if !text.is_empty() => decrypt_with_passphrase(&text, passphrase),
+        _ => Ok(text),
+    }
+}
+
+fn load_revisions(
+    conn: &Connection,
+    key: &Key<Aes256Gcm>,
+    note_id: i32,
+    passphrase: Option<&str>,
+) -> anyhow::Result<Vec<Version>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, note, body, priority, revisedAt FROM note_revisions WHERE note_id = ?1 ORDER BY id ASC"
+    ).context("Failed to prepare statement")?;
+    let rows = stmt.query_and_then(params![note_id], |row| -> anyhow::Result<Version> {
+        let encrypted_title: Vec<u8> = row.get(1).context("Failed to get revision title from row")?;
+        let encrypted_body: Option<Vec<u8>> = row.get(2).context("Failed to get revision body from row")?;
+        let encrypted_priority: Vec<u8> = row.get(3).context("Failed to get revision priority from row")?;
+        let revised_at: String = row.get(4).context("Failed to get revisedAt from row")?;
+        let body = encrypted_body.map(|b| decrypt_data(&b, key)).transpose()?.unwrap_or_default();
+        Ok(Version {
+            revision_id: Some(row.get(0).context("Failed to get revision id from row")?),
+            title: unseal(decrypt_data(&encrypted_title, key)?, passphrase)?,
+            body: unseal(body, passphrase)?,
+            priority: decrypt_data(&encrypted_priority, key)?,
+            at: DateTime::parse_from_rfc3339(&revised_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .context("Failed to parse revisedAt")?,
+        })
+    }).context("Failed to query revisions")?;
+    rows.collect()
+}
+
+// Memecah teks menjadi kata dan spasi di antaranya agar format asli tetap terjaga
+fn diff_tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut in_space = None;
+    for (i, c) in text.char_indices() {
+        let space = c.is_whitespace();
+        if in_space.is_some_and(|s| s != space) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        in_space = Some(space);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+// Panjang LCS `a` dengan setiap awalan `b`, hanya satu baris tabel yang disimpan
+fn lcs_lengths(a: &[&str], b: &[&str]) -> Vec<usize> {
+    let mut row = vec![0usize; b.len() + 1];
+    for token in a {
+        let mut diagonal = 0;
+        for j in 0..b.len() {
+            let above = row[j + 1];
+            row[j + 1] = if *token == b[j] { diagonal + 1 } else { row[j + 1].max(row[j]) };
+            diagonal = above;
+        }
+    }
+    row
+}
+
+// Algoritma Hirschberg: LCS dengan memori linear; hasilnya token berjenis 0 sama, -1 dihapus, 1 ditambah
+fn diff_ops<'a>(old: &[&'a str], new: &[&'a str], ops: &mut Vec<(i8, &'a str)>) {
+    if old.is_empty() {
+        ops.extend(new.iter().map(|t| (1, *t)));
+    } else if new.is_empty() {
+        ops.extend(old.iter().map(|t| (-1, *t)));
+    } else if old.len() == 1 {
+        match new.iter().position(|t| *t == old[0]) {
+            Some(k) => {
+                ops.extend(new[..k].iter().map(|t| (1, *t)));
+                ops.push((0, old[0]));
+                ops.extend(new[k + 1..].iter().map(|t| (1, *t)));
+            }
+            None => {
+                ops.extend(new.iter().map(|t| (1, *t)));
+                ops.push((-1, old[0]));
+            }
+        }
+    } else {
+        let mid = old.len() / 2;
+        let forward = lcs_lengths(&old[..mid], new);
+        let old_rev: Vec<&str> = old[mid..].iter().rev().copied().collect();
+        let new_rev: Vec<&str> = new.iter().rev().copied().collect();
+        let backward = lcs_lengths(&old_rev, &new_rev);
+        let split = (0..=new.len())
+            .max_by_key(|&j| (forward[j] + backward[new.len() - j], std::cmp::Reverse(j)))
+            .unwrap_or(0);
+        diff_ops(&old[..mid], &new[..split], ops);
+        diff_ops(&old[mid..], &new[split..], ops);
+    }
+}
+
+// Diff per kata berdasarkan longest common subsequence; awalan dan akhiran yang sama dilewati dulu
+fn word_diff(old: &str, new: &str) -> String {
+    let (old, new) = (diff_tokens(old), diff_tokens(new));
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+
+    let mut ops: Vec<(i8, &str)> = old[..prefix].iter().map(|t| (0, *t)).collect();
+    diff_ops(&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix], &mut ops);
+    ops.extend(old[old.len() - suffix..].iter().map(|t| (0, *t)));
+
+    // Token berurutan dengan jenis yang sama digabung
+    let mut runs: Vec<(i8, String)> = Vec::new();
+    for (kind, token) in ops {
+        match runs.last_mut() {
+            Some((last, text)) if *last == kind => text.push_str(token),
+            _ => runs.push((kind, token.to_string())),
+        }
+    }
+
+    let color = use_color();
+    runs.iter()
+        .map(|(kind, text)| match (kind, color) {
+            (0, _) => text.clone(),
+            (-1, true) => format!("{}{}{}", REMOVED_START, text, COLOR_END),
+            (-1, false) => format!("[-{}-]", text),
+            (_, true) => format!("{}{}{}", ADDED_START, text, COLOR_END),
+            (_, false) => format!("{{+{}+}}", text),
+        })
+        .collect()
+}
+
+fn print_versions(versions: &[Version]) {
+    println!("| {:<4} | {:<19} | {:<10} | {:<50} |", "No", "Waktu", "Prioritas", "Judul");
+    println!("|------|---------------------|------------|----------------------------------------------------|");
+    for (number, version) in versions.iter().enumerate() {
+        let label = match version.revision_id {
+            Some(_) => (number + 1).to_string(),
+            None => "0".to_string(),
+        };
+        let title: String = version.title.chars().take(50).collect();
+        println!(
+            "| {:<4} | {:<19} | {:<10} | {:<50} |",
+            label, version.at.format("%Y-%m-%d %H:%M:%S").to_string(), version.priority, title
+        );
+    }
+    println!("(0 = versi sekarang)");
+}
+
+// Nomor versi seperti yang ditampilkan; 0 adalah versi sekarang yang selalu terakhir di daftar
+fn read_version<'a>(versions: &'a [Version], prompt: &str) -> anyhow::Result<Option<&'a Version>> {
+    println!("{}", prompt);
+    let mut input = String::new();
+    read_input(&mut input)?;
+    let version = match input.trim().parse::<usize>() {
+        Ok(0) => versions.last(),
+        Ok(number) if number < versions.len() => versions.get(number - 1),
+        _ => None,
+    };
+    if version.is_none() {
+        println!("Nomor revisi tidak valid!");
+    }
+    Ok(version)
+}
+
+pub fn history_menu(conn: &Connection, key: &Key<Aes256Gcm>, note_id: i32) -> anyhow::Result<()> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM notes WHERE id = ?1 AND deletedAt IS NULL", NOTE_COLUMNS))
+        .context("Failed to prepare statement")?;
+    let Some(note) = stmt.query_and_then(params![note_id], |row| note_from_row(row, key))
+        .context("Failed to query note by ID")?
+        .next()
+        .transpose()?
+    else {
+        println!("Catatan dengan ID {} tidak ditemukan!", note_id);
+        return Ok(());
+    };
+
+    let passphrase = if note.secret {
+        let Some(passphrase) = read_passphrase(false)? else {
+            return Ok(());
+        };
+        if decrypt_with_passphrase(&note.title, &passphrase).is_err() {
+            println!("Passphrase salah!");
+            return Ok(());
+        }
+        Some(passphrase)
+    } else {
+        None
+    };
+    let passphrase = passphrase.as_deref();
+
+    let mut versions = load_revisions(conn, key, note_id, passphrase)?;
+    println!("\nRiwayat Revisi Catatan {} ({} revisi):", note_id, versions.len());
+    if versions.is_empty() {
+        println!("Belum ada revisi. Revisi disimpan setiap kali catatan diedit.");
+        return Ok(());
+    }
+    versions.push(Version {
+        revision_id: None,
+        title: unseal(note.title.clone(), passphrase)?,
+        body: unseal(note.body.clone(), passphrase)?,
+        priority: note.priority.clone(),
+        at: note.modified_at.unwrap_or(note.created_at),
+    });
+    print_versions(&versions);
+
+    println!("\nMenu:");
+    println!("1. Bandingkan Dua Revisi");
+    println!("2. Kembalikan ke Revisi");
+    println!("3. Kembali");
+    println!("Pilih opsi (1-3): ");
+
+    let mut choice = String::new();
+    read_input(&mut choice)?;
+    let choice: i32 = choice.trim().parse().unwrap_or(0);
+
+    match choice {
+        1 => {
+            let Some(old) = read_version(&versions, "Nomor revisi lama: ")? else {
+                return Ok(());
+            };
+            let Some(new) = read_version(&versions, "Nomor revisi baru (0 untuk versi sekarang): ")? else {
+                return Ok(());
+            };
+            println!();
+            if old.priority != new.priority {
+                println!("Prioritas: {} -> {}", old.priority, new.priority);
+            }
+            if old.text() == new.text() {
+                println!("Judul dan isi tidak berubah.");
+            } else {
+                println!("{}", word_diff(&old.text(), &new.text()));
+            }
+        }
+        2 => {
+            let Some(version) = read_version(&versions, "Nomor revisi yang akan dikembalikan: ")? else {
+                return Ok(());
+            };
+            let Some(revision_id) = version.revision_id else {
+                println!("Versi sekarang tidak perlu dikembalikan.");
+                return Ok(());
+            };
+            // Versi sekarang disimpan dulu agar pengembalian juga bisa dibatalkan
+            save_revision(conn, note_id)?;
+            conn.execute(
+                "UPDATE notes SET
+                     note = (SELECT note FROM note_revisions WHERE id = ?1),
+                     body = (SELECT body FROM note_revisions WHERE id = ?1),
+                     priority = (SELECT priority FROM note_revisions WHERE id = ?1),
+                     modifiedAt = ?2
+                 WHERE id = ?3",
+                params![revision_id, Utc::now().to_rfc3339(), note_id],
+            ).context("Failed to revert note")?;
+            if !note.secret {
+                index_note(conn, key, note_id, &version.text())?;
+            }
+            println!("Catatan dengan ID {} dikembalikan ke revisi {}.", note_id, version.at.format("%Y-%m-%d %H:%M:%S"));
+        }
+        3 => println!("Kembali."),
+        _ => println!("Pilihan tidak valid!"),
+    }
+    Ok(())
+}
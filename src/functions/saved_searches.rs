@@ -0,0 +1,159 @@
+use rusqlite::{Connection, params};
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use crate::functions::search_notes::{SearchOptions, SortOrder, display_query, mode_name, run_search};
+use crate::functions::utils::{decrypt_data, encrypt_data, read_input};
+
+// Pencarian bernama: mode, query (termasuk filter) dan urutan hasil
+pub struct SavedSearch {
+    pub name: String,
+    pub mode: String,
+    pub query: String,
+    pub sort: SortOrder,
+    pub stemming: bool,
+    pub pinned: bool,
+}
+
+impl SavedSearch {
+    pub fn run(&self, conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+        run_search(conn, key, &self.mode, &self.query, SearchOptions { sort: self.sort, snippet: false, stemming: self.stemming })
+    }
+}
+
+// Ditawarkan setelah pencarian dari menu selesai dijalankan
+pub fn offer_save_search(conn: &Connection, key: &Key<Aes256Gcm>, mode: &str, query: &str, options: SearchOptions) -> anyhow::Result<()> {
+    println!("\nSimpan pencarian ini dengan nama (kosongkan untuk tidak menyimpan): ");
+    let mut name = String::new();
+    read_input(&mut name)?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Ok(());
+    }
+
+    // Query dienkripsi karena bisa memuat isi catatan
+    let encrypted_query = encrypt_data(query, key)?;
+    conn.execute(
+        "INSERT INTO saved_searches (name, mode, query, sort, stemming) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(name) DO UPDATE SET mode = excluded.mode, query = excluded.query, sort = excluded.sort,
+             stemming = excluded.stemming",
+        params![name, mode, encrypted_query, options.sort.as_str(), options.stemming],
+    ).context("Failed to save search")?;
+    println!("Pencarian '{}' berhasil disimpan!", name);
+    Ok(())
+}
+
+pub fn load_saved_searches(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<Vec<SavedSearch>> {
+    let mut stmt = conn.prepare("SELECT name, mode, query, sort, pinned, stemming FROM saved_searches ORDER BY name ASC")
+        .context("Failed to prepare statement")?;
+    let search_iter = stmt.query_and_then(
+        [],
+        |row| -> anyhow::Result<SavedSearch> {
+            let encrypted_query: Vec<u8> = row.get(2)
+                .context("Failed to get query from row")?;
+            let sort: String = row.get(3)
+                .context("Failed to get sort from row")?;
+            Ok(SavedSearch {
+                name: row.get(0).context("Failed to get name from row")?,
+                mode: row.get(1).context("Failed to get mode from row")?,
+                query: decrypt_data(&encrypted_query, key).context("Failed to decrypt query")?,
+                sort: SortOrder::from_choice(&sort),
+                stemming: row.get(5).context("Failed to get stemming from row")?,
+                pinned: row.get(4).context("Failed to get pinned from row")?,
+            })
+        }
+    ).context("Failed to query saved searches")?;
+    search_iter.collect()
+}
+
+// Pencarian yang dijadikan tampilan default di atas menu utama
+pub fn pinned_search(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<Option<SavedSearch>> {
+    Ok(load_saved_searches(conn, key)?.into_iter().find(|s| s.pinned))
+}
+
+pub fn run_saved_search(conn: &Connection, key: &Key<Aes256Gcm>, name: &str) -> anyhow::Result<()> {
+    match load_saved_searches(conn, key)?.into_iter().find(|s| s.name == name) {
+        Some(search) => search.run(conn, key),
+        None => {
+            println!("Pencarian tersimpan '{}' tidak ditemukan!", name);
+            Ok(())
+        }
+    }
+}
+
+fn list_saved_searches(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<Vec<SavedSearch>> {
+    let searches = load_saved_searches(conn, key)?;
+    println!("\nDaftar Pencarian Tersimpan:");
+    println!("| {:<20} | {:<16} | {:<40} | {:<10} | {:<7} |", "Nama", "Mode", "Query", "Urutan", "Default");
+    println!("|----------------------|------------------|------------------------------------------|------------|---------|");
+    for search in &searches {
+        println!(
+            "| {:<20} | {:<16} | {:<40} | {:<10} | {:<7} |",
+            search.name,
+            if search.stemming { format!("{} +stem", mode_name(&search.mode)) } else { mode_name(&search.mode).to_string() },
+            display_query(&search.mode, &search.query),
+            search.sort.label(),
+            if search.pinned { "Ya" } else { "" }
+        );
+    }
+    if searches.is_empty() {
+        println!("Belum ada pencarian tersimpan. Simpan dari menu Search Catatan.");
+    }
+    Ok(searches)
+}
+
+pub fn saved_searches_menu(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    let searches = list_saved_searches(conn, key)?;
+    if searches.is_empty() {
+        return Ok(());
+    }
+    println!("\nMenu:");
+    println!("1. Jalankan Pencarian");
+    println!("2. Hapus Pencarian");
+    println!("3. Jadikan Tampilan Default");
+    println!("4. Hapus Tampilan Default");
+    println!("5. Kembali ke Menu Utama");
+    println!("Pilih opsi (1-5): ");
+
+    let mut choice = String::new();
+    read_input(&mut choice)?;
+    let choice: i32 = choice.trim().parse().unwrap_or(0);
+
+    if choice == 4 {
+        conn.execute("UPDATE saved_searches SET pinned = 0", [])
+            .context("Failed to unpin saved search")?;
+        println!("Daftar catatan kembali ke tampilan biasa.");
+        return Ok(());
+    }
+    if !(1..=3).contains(&choice) {
+        if choice != 5 {
+            println!("Pilihan tidak valid!");
+        }
+        return Ok(());
+    }
+
+    println!("Masukkan nama pencarian: ");
+    let mut name = String::new();
+    read_input(&mut name)?;
+    let name = name.trim();
+    let Some(search) = searches.iter().find(|s| s.name == name) else {
+        println!("Pencarian tersimpan '{}' tidak ditemukan!", name);
+        return Ok(());
+    };
+
+    match choice {
+        1 => search.run(conn, key)?,
+        2 => {
+            conn.execute("DELETE FROM saved_searches WHERE name = ?1", params![search.name])
+                .context("Failed to delete saved search")?;
+            println!("Pencarian '{}' berhasil dihapus!", search.name);
+        }
+        _ => {
+            conn.execute(
+                "UPDATE saved_searches SET pinned = (name = ?1)",
+                params![search.name],
+            ).context("Failed to pin saved search")?;
+            println!("Pencarian '{}' sekarang menjadi tampilan default.", search.name);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use crate::functions::blind_index::words;
+use crate::functions::stemmer::stem;
+
+// Parameter BM25 yang umum dipakai
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Term {
+    Word(String),
+    Prefix(String),
+    Phrase(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Query {
+    Term(Term),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+}
+
+// Catatan yang sudah didekripsi dan dipecah menjadi kata
+pub struct Document {
+    pub id: i32,
+    pub words: Vec<String>,
+}
+
+enum Token {
+    Word(String),
+    Phrase(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+fn lex(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '-' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            '"' => {
+                chars.next();
+                let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                tokens.push(Token::Phrase(phrase));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn parse_or(&mut self) -> Option<Query> {
+        let mut parts: Vec<Query> = self.parse_and().into_iter().collect();
+        while let Some(Token::Or) = self.peek() {
+            self.pos += 1;
+            parts.extend(self.parse_and());
+        }
+        combine(parts, Query::Or)
+    }
+
+    fn parse_and(&mut self) -> Option<Query> {
+        let mut parts = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some(Token::Close) | Some(Token::Or) => break,
+                Some(Token::And) => self.pos += 1,
+                _ => parts.extend(self.parse_unary()),
+            }
+        }
+        combine(parts, Query::And)
+    }
+
+    fn parse_unary(&mut self) -> Option<Query> {
+        let token = self.tokens.get(self.pos)?;
+        self.pos += 1;
+        match token {
+            Token::Not => self.parse_unary().map(|q| Query::Not(Box::new(q))),
+            Token::Open => {
+                let query = self.parse_or();
+                if let Some(Token::Close) = self.peek() {
+                    self.pos += 1;
+                }
+                query
+            }
+            Token::Word(raw) => word_term(raw).map(Query::Term),
+            Token::Phrase(raw) => phrase_term(raw).map(Query::Term),
+            _ => None,
+        }
+    }
+}
+
+fn combine(mut parts: Vec<Query>, op: fn(Vec<Query>) -> Query) -> Option<Query> {
+    match parts.len() {
+        0 => None,
+        1 => parts.pop(),
+        _ => Some(op(parts)),
+    }
+}
+
+fn word_term(raw: &str) -> Option<Term> {
+    if let Some(stem) = raw.strip_suffix('*') {
+        let mut stem = words(stem);
+        return match stem.len() {
+            1 => stem.pop().map(Term::Prefix),
+            _ => None,
+        };
+    }
+    phrase_term(raw)
+}
+
+fn phrase_term(raw: &str) -> Option<Term> {
+    let mut parts = words(raw);
+    match parts.len() {
+        0 => None,
+        1 => parts.pop().map(Term::Word),
+        _ => Some(Term::Phrase(parts)),
+    }
+}
+
+// Sintaks: kata, "frasa", awalan*, AND, OR, NOT atau -kata, dan tanda kurung
+pub fn parse_query(input: &str) -> Option<Query> {
+    let mut parser = Parser { tokens: lex(input), pos: 0 };
+    let mut parts = Vec::new();
+    while parser.pos < parser.tokens.len() {
+        let start = parser.pos;
+        match parser.parse_or() {
+            Some(query) => parts.push(query),
+            // Tanda kurung tutup berlebih dilewati
+            None if parser.pos == start => parser.pos += 1,
+            None => {}
+        }
+    }
+    combine(parts, Query::And)
+}
+
+// Kata dan frasa diganti bentuk dasarnya; awalan* tetap dicocokkan apa adanya
+pub fn stem_query(query: Query) -> Query {
+    match query {
+        Query::Term(Term::Word(word)) => Query::Term(Term::Word(stem(&word))),
+        Query::Term(Term::Phrase(phrase)) => Query::Term(Term::Phrase(phrase.iter().map(|w| stem(w)).collect())),
+        Query::Term(term) => Query::Term(term),
+        Query::And(parts) => Query::And(parts.into_iter().map(stem_query).collect()),
+        Query::Or(parts) => Query::Or(parts.into_iter().map(stem_query).collect()),
+        Query::Not(inner) => Query::Not(Box::new(stem_query(*inner))),
+    }
+}
+
+fn term_frequency(term: &Term, words: &[String]) -> usize {
+    match term {
+        Term::Word(word) => words.iter().filter(|w| *w == word).count(),
+        Term::Prefix(prefix) => words.iter().filter(|w| w.starts_with(prefix.as_str())).count(),
+        Term::Phrase(phrase) => words.windows(phrase.len()).filter(|w| w == phrase).count(),
+    }
+}
+
+fn matches(query: &Query, words: &[String]) -> bool {
+    match query {
+        Query::Term(term) => term_frequency(term, words) > 0,
+        Query::And(parts) => parts.iter().all(|q| matches(q, words)),
+        Query::Or(parts) => parts.iter().any(|q| matches(q, words)),
+        Query::Not(inner) => !matches(inner, words),
+    }
+}
+
+// Istilah yang ikut menentukan skor (bukan yang berada di bawah NOT)
+fn positive_terms<'a>(query: &'a Query, negated: bool, out: &mut Vec<&'a Term>) {
+    match query {
+        Query::Term(term) => {
+            if !negated && !out.contains(&term) {
+                out.push(term);
+            }
+        }
+        Query::And(parts) | Query::Or(parts) => {
+            for part in parts {
+                positive_terms(part, negated, out);
+            }
+        }
+        Query::Not(inner) => positive_terms(inner, !negated, out),
+    }
+}
+
+// Mengembalikan ID dan skor BM25 catatan yang cocok, skor tertinggi lebih dulu
+pub fn rank(query: &Query, documents: &[Document]) -> Vec<(i32, f64)> {
+    let mut terms = Vec::new();
+    positive_terms(query, false, &mut terms);
+
+    let total = documents.len() as f64;
+    let avg_len = (documents.iter().map(|d| d.words.len()).sum::<usize>() as f64 / total.max(1.0)).max(1.0);
+    let doc_freq: HashMap<&Term, f64> = terms.iter()
+        .map(|&term| {
+            let df = documents.iter().filter(|d| term_frequency(term, &d.words) > 0).count();
+            (term, df as f64)
+        })
+        .collect();
+
+    let mut results: Vec<(i32, f64)> = documents.iter()
+        .filter(|doc| matches(query, &doc.words))
+        .map(|doc| {
+            let len = doc.words.len() as f64;
+            let score = terms.iter()
+                .map(|&term| {
+                    let tf = term_frequency(term, &doc.words) as f64;
+                    let df = doc_freq[term];
+                    let idf = ((total - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    idf * tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * len / avg_len))
+                })
+                .sum();
+            (doc.id, score)
+        })
+        .collect();
+    results.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    results
+}
+
+// Rentang byte di teks asli yang cocok dengan istilah positif query, untuk penyorotan
+pub fn match_ranges(query: &Query, spans: &[(usize, usize, String)]) -> Vec<(usize, usize)> {
+    let mut terms = Vec::new();
+    positive_terms(query, false, &mut terms);
+    let mut ranges = Vec::new();
+    for term in terms {
+        match term {
+            Term::Word(word) => ranges.extend(
+                spans.iter().filter(|s| &s.2 == word).map(|s| (s.0, s.1)),
+            ),
+            Term::Prefix(prefix) => ranges.extend(
+                spans.iter().filter(|s| s.2.starts_with(prefix.as_str())).map(|s| (s.0, s.1)),
+            ),
+            Term::Phrase(phrase) => ranges.extend(
+                spans.windows(phrase.len())
+                    .filter(|w| w.iter().zip(phrase).all(|(s, p)| &s.2 == p))
+                    .map(|w| (w[0].0, w[w.len() - 1].1)),
+            ),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_matches(query: &str, text: &str) -> bool {
+        let query = parse_query(query).expect("query should parse");
+        matches(&query, &words(text))
+    }
+
+    #[test]
+    fn words_are_joined_with_and() {
+        assert!(matches!(parse_query("rapat tim"), Some(Query::And(parts)) if parts.len() == 2));
+        assert!(query_matches("rapat tim", "Rapat tim mingguan"));
+        assert!(!query_matches("rapat tim", "Rapat direksi"));
+    }
+
+    #[test]
+    fn or_binds_looser_than_and() {
+        let query = parse_query("rapat AND tim OR laporan").expect("query should parse");
+        assert!(matches!(&query, Query::Or(parts) if parts.len() == 2 && matches!(parts[0], Query::And(_))));
+        assert!(matches(&query, &words("laporan pajak")));
+        assert!(matches(&query, &words("rapat tim")));
+        assert!(!matches(&query, &words("rapat direksi")));
+    }
+
+    #[test]
+    fn not_and_minus_exclude_terms() {
+        assert!(query_matches("rapat NOT direksi", "rapat tim"));
+        assert!(!query_matches("rapat NOT direksi", "rapat direksi"));
+        assert!(!query_matches("rapat -direksi", "rapat direksi"));
+    }
+
+    #[test]
+    fn parentheses_group_terms() {
+        assert!(query_matches("(tim OR direksi) rapat", "rapat direksi"));
+        assert!(!query_matches("(tim OR direksi) rapat", "rapat klien"));
+        assert!(query_matches("rapat (tim", "rapat tim"));
+        assert!(query_matches("rapat) tim", "rapat tim"));
+    }
+
+    #[test]
+    fn phrases_must_be_adjacent() {
+        assert!(matches!(
+            parse_query("\"laporan pajak\""),
+            Some(Query::Term(Term::Phrase(phrase))) if phrase == ["laporan", "pajak"]
+        ));
+        assert!(query_matches("\"laporan pajak\"", "Kirim laporan pajak tahunan"));
+        assert!(!query_matches("\"laporan pajak\"", "laporan tahunan pajak"));
+    }
+
+    #[test]
+    fn prefix_matches_word_starts() {
+        assert!(matches!(parse_query("lapor*"), Some(Query::Term(Term::Prefix(p))) if p == "lapor"));
+        assert!(query_matches("lapor*", "Laporan bulanan"));
+        assert!(!query_matches("lapor*", "melapor"));
+    }
+
+    #[test]
+    fn empty_queries_parse_to_nothing() {
+        assert!(parse_query("").is_none());
+        assert!(parse_query("  ( ) ").is_none());
+        assert!(parse_query("AND OR").is_none());
+    }
+
+    #[test]
+    fn rank_puts_frequent_terms_first() {
+        let documents = vec![
+            Document { id: 1, words: words("rapat tim") },
+            Document { id: 2, words: words("rapat rapat rapat") },
+            Document { id: 3, words: words("laporan pajak") },
+        ];
+        let query = parse_query("rapat").expect("query should parse");
+        let ids: Vec<i32> = rank(&query, &documents).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![2, 1]);
+    }
+}
@@ -1,62 +1,504 @@
-use rusqlite::Connection;
-use textwrap::wrap;
+use rusqlite::{Connection, params};
 use aes_gcm::{Aes256Gcm, Key};
 use anyhow::Context;
-use crate::functions::utils::{Note, decrypt_data};
+use regex::RegexBuilder;
+use crate::functions::blind_index::{lookup, stems, tokenize, word_spans};
+use crate::functions::fuzzy::{FuzzyMatch, fuzzy_search};
+use crate::functions::highlight::{highlight_lines, snippet};
+use crate::functions::query_filter::{NoteFilter, parse_filter};
+use crate::functions::search_engine::{Document, match_ranges, parse_query, rank, stem_query};
+use crate::functions::saved_searches::offer_save_search;
+use crate::functions::checklist::checklist_map;
+use crate::functions::tags::note_tag_map;
+use crate::functions::stemmer::{Language, language, stem};
+use crate::functions::utils::{NOTE_COLUMNS, Note, note_from_row, read_input};
+use crate::functions::priorities::Priorities;
+use crate::functions::markdown::{self, table_text};
+
+// Batas ukuran regex yang sudah dikompilasi agar pola berlebihan ditolak
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    Relevance,
+    Created,
+    Modified,
+    Priority,
+}
+
+impl SortOrder {
+    pub fn from_choice(choice: &str) -> SortOrder {
+        match choice {
+            "2" | "created" => SortOrder::Created,
+            "3" | "modified" => SortOrder::Modified,
+            "4" | "priority" => SortOrder::Priority,
+            _ => SortOrder::Relevance,
+        }
+    }
+
+    // Nilai yang disimpan di database untuk pencarian tersimpan
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SortOrder::Relevance => "relevance",
+            SortOrder::Created => "created",
+            SortOrder::Modified => "modified",
+            SortOrder::Priority => "priority",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortOrder::Relevance => "relevansi",
+            SortOrder::Created => "dibuat",
+            SortOrder::Modified => "diubah",
+            SortOrder::Priority => "prioritas",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SearchOptions {
+    pub sort: SortOrder,
+    pub snippet: bool,
+    // Cocokkan bentuk dasar kata, misalnya "makan" dengan "makanan" dan "dimakan"
+    pub stemming: bool,
+}
+
+pub fn mode_name(mode: &str) -> &'static str {
+    match mode {
+        "2" => "Lanjutan",
+        "3" => "Fuzzy",
+        "4" => "Regex",
+        _ => "Kata kunci",
+    }
+}
+
+// Urutan bawaan tiap mode (relevansi atau ID) dipertahankan untuk nilai yang sama
+fn sort_results<T>(items: &mut [T], priorities: &Priorities, order: SortOrder, note: fn(&T) -> &Note) {
+    match order {
+        SortOrder::Relevance => {}
+        SortOrder::Created => items.sort_by_key(|item| std::cmp::Reverse(note(item).created_at)),
+        SortOrder::Modified => items.sort_by_key(|item| {
+            let note = note(item);
+            std::cmp::Reverse(note.modified_at.unwrap_or(note.created_at))
+        }),
+        SortOrder::Priority => items.sort_by_key(|item| priorities.rank(&note(item).priority)),
+    }
+}
 
 pub fn search_notes(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
-    println!("Masukkan kata kunci untuk mencari catatan: ");
-    let mut keyword = String::new();
-    std::io::stdin().read_line(&mut keyword)?;
-    let keyword = keyword.trim();
-
-    let mut stmt = conn.prepare("SELECT id, note, priority FROM notes")
-        .context("Failed to prepare statement")?;
-
-    let note_iter = stmt.query_and_then(
-        [],
-        |row| -> anyhow::Result<Note> {
-            let encrypted_note: Vec<u8> = row.get(1)
-                .context("Failed to get note from row")?;
-            let encrypted_priority: Vec<u8> = row.get(2)
-                .context("Failed to get priority from row")?;
-            let note = decrypt_data(&encrypted_note, key)
-                .context("Failed to decrypt note")?;
-            let priority = decrypt_data(&encrypted_priority, key)
-                .context("Failed to decrypt priority")?;
-            Ok(Note {
-                id: row.get(0).context("Failed to get id from row")?,
-                note,
-                priority,
-                created_at: chrono::Utc::now(), // Dummy
-                modified_at: None, // Dummy
-            })
+    println!("Mode pencarian:");
+    println!("1. Kata kunci (cepat, lewat indeks)");
+    println!("2. Lanjutan (AND, OR, NOT, \"frasa\", awalan*, urut berdasarkan relevansi)");
+    println!("3. Fuzzy (toleran salah ketik)");
+    println!("4. Regex (pola seperti nomor tiket, tanggal, URL)");
+    println!("Pilih mode (default 1): ");
+    let mut mode = String::new();
+    read_input(&mut mode)?;
+    let mode = mode.trim().to_string();
+
+    let mut input = String::new();
+    if mode == "4" {
+        println!("Masukkan pola regex: ");
+        read_input(&mut input)?;
+        // Pola dipakai apa adanya, hanya akhir baris yang dibuang
+        let mut pattern = input.trim_end_matches(['\r', '\n']).to_string();
+        println!("Abaikan huruf besar/kecil? (Y/n): ");
+        let mut case_choice = String::new();
+        read_input(&mut case_choice)?;
+        // Flag disimpan di dalam pola agar pencarian tersimpan tetap sama
+        if !case_choice.trim().eq_ignore_ascii_case("n") {
+            pattern = format!("(?i){}", pattern);
+        }
+        println!("Filter catatan (mis. prio:tinggi tag:kerja folder:kerja is:archived, kosongkan jika tidak ada): ");
+        let mut filters = String::new();
+        read_input(&mut filters)?;
+        input = regex_query(&pattern, filters.trim());
+    } else {
+        println!("Masukkan query pencarian (filter: prio:tinggi tag:kerja folder:kerja todo:open is:pinned created:>=2025-03-01 modified:<7d): ");
+        read_input(&mut input)?;
+        input = input.trim().to_string();
+    }
+    let input = input.as_str();
+
+    println!("Urutkan (1: relevansi, 2: terbaru dibuat, 3: terbaru diubah, 4: prioritas; default 1): ");
+    let mut sort_choice = String::new();
+    read_input(&mut sort_choice)?;
+    let sort = SortOrder::from_choice(sort_choice.trim());
+
+    println!("Tampilkan hanya cuplikan di sekitar kata yang cocok? (y/N): ");
+    let mut snippet_choice = String::new();
+    read_input(&mut snippet_choice)?;
+    let snippet = snippet_choice.trim().eq_ignore_ascii_case("y");
+
+    // Regex mencocokkan teks mentah sehingga stemming tidak berlaku
+    let mut stemming = false;
+    if mode != "4" {
+        let language_name = match language() {
+            Language::Indonesian => "Indonesia",
+            Language::English => "Inggris",
+        };
+        println!("Cocokkan bentuk dasar kata (stemming bahasa {})? (y/N): ", language_name);
+        let mut stemming_choice = String::new();
+        read_input(&mut stemming_choice)?;
+        stemming = stemming_choice.trim().eq_ignore_ascii_case("y");
+    }
+
+    let options = SearchOptions { sort, snippet, stemming };
+    run_search(conn, key, &mode, input, options)?;
+    offer_save_search(conn, key, &mode, input, options)
+}
+
+// Pola regex dan filternya disimpan dalam satu query, dipisah baris baru yang tidak bisa diketik di prompt satu baris
+const REGEX_FILTER_SEPARATOR: char = '\n';
+
+pub fn regex_query(pattern: &str, filters: &str) -> String {
+    if filters.is_empty() {
+        pattern.to_string()
+    } else {
+        format!("{}{}{}", pattern, REGEX_FILTER_SEPARATOR, filters)
+    }
+}
+
+// Pola regex dan bagian filternya; query tanpa pemisah seluruhnya adalah pola
+pub fn split_regex_query(query: &str) -> (&str, &str) {
+    query.split_once(REGEX_FILTER_SEPARATOR).unwrap_or((query, ""))
+}
+
+// Query untuk ditampilkan di satu baris
+pub fn display_query(mode: &str, query: &str) -> String {
+    match split_regex_query(query) {
+        (pattern, filters) if mode == "4" && !filters.is_empty() => format!("{} [{}]", pattern, filters),
+        _ => query.to_string(),
+    }
+}
+
+// Menjalankan pencarian tanpa prompt, dipakai juga oleh pencarian tersimpan
+pub fn run_search(conn: &Connection, key: &Key<Aes256Gcm>, mode: &str, input: &str, options: SearchOptions) -> anyhow::Result<()> {
+    match mode {
+        "2" => ranked_search(conn, key, input, options),
+        "3" => print_fuzzy_results(conn, key, input, options),
+        "4" => regex_search(conn, key, input, options),
+        _ => keyword_search(conn, key, input, options),
+    }
+}
+
+// Isi catatan dengan kecocokan disorot, dibungkus ke lebar kolom Note
+fn render_note(text: &str, ranges: &[(usize, usize)], snippet_mode: bool) -> Vec<String> {
+    if snippet_mode {
+        let (text, ranges) = snippet(text, ranges);
+        highlight_lines(&text, &ranges, 60)
+    } else {
+        highlight_lines(text, ranges, 60)
+    }
+}
+
+// Posisi kata beserta bentuk normalnya, atau bentuk dasarnya jika stemming aktif
+fn search_spans(text: &str, stemming: bool) -> Vec<(usize, usize, String)> {
+    let spans = word_spans(text);
+    if !stemming {
+        return spans;
+    }
+    spans.into_iter().map(|(start, end, word)| (start, end, stem(&word))).collect()
+}
+
+fn search_words(text: &str, stemming: bool) -> Vec<String> {
+    search_spans(text, stemming).into_iter().map(|(_, _, word)| word).collect()
+}
+
+// Rentang kata di teks yang bentuk normalnya termasuk dalam daftar kata
+fn word_ranges(text: &str, targets: &[&String], stemming: bool) -> Vec<(usize, usize)> {
+    search_spans(text, stemming).into_iter()
+        .filter(|(_, _, word)| targets.contains(&word))
+        .map(|(start, end, _)| (start, end))
+        .collect()
+}
+
+fn read_filter(input: &str) -> Option<NoteFilter> {
+    match parse_filter(input) {
+        Ok(filter) => Some(filter),
+        Err(e) => {
+            println!("{}", e);
+            None
         }
-    ).context("Failed to query notes")?;
+    }
+}
+
+fn keyword_search(conn: &Connection, key: &Key<Aes256Gcm>, input: &str, options: SearchOptions) -> anyhow::Result<()> {
+    let Some(filter) = read_filter(input) else {
+        return Ok(());
+    };
 
-    let mut found = false;
-    println!("\nHasil Pencarian untuk '{}':", keyword);
+    // Hanya catatan yang token kata kuncinya cocok di indeks yang didekripsi
+    let notes = if filter.text.is_empty() {
+        load_notes(conn, key)?
+    } else {
+        let ids = lookup(conn, key, &filter.text, options.stemming)?;
+        load_notes_by_id(conn, key, &ids)?
+    };
+
+    let keywords = if options.stemming { stems(&filter.text) } else { tokenize(&filter.text) };
+    let keywords: Vec<&String> = keywords.iter().collect();
+    let mut results: Vec<&Note> = notes.iter().filter(|n| filter.matches_fields(n)).collect();
+    let priorities = Priorities::load(conn, key)?;
+    sort_results(&mut results, &priorities, options.sort, |n| n);
+
+    println!("\nHasil Pencarian untuk '{}':", input);
     println!("| {:<4} | {:<60} | {:<10} |", "ID", "Note", "Priority");
     println!("|------|--------------------------------------------------------------|------------|");
 
-    for note in note_iter {
-        let note = note?;
-        if note.note.to_lowercase().contains(&keyword.to_lowercase()) {
-            found = true;
-            let wrapped_note = wrap(&note.note, 60);
-            for (i, line) in wrapped_note.iter().enumerate() {
-                if i == 0 {
-                    println!("| {:<4} | {:<60} | {:<10} |", note.id, line, note.priority);
-                } else {
-                    println!("| {:<4} | {:<60} | {:<10} |", "", line, "");
-                }
+    for note in &results {
+        let text = table_text(&note.text());
+        let lines = render_note(&text, &word_ranges(&text, &keywords, options.stemming), options.snippet);
+        for (i, line) in lines.iter().enumerate() {
+            if i == 0 {
+                println!("| {:<4} | {} | {} |", note.id, line, priorities.label(&note.priority, 10));
+            } else {
+                println!("| {:<4} | {} | {:<10} |", "", line, "");
             }
-            println!("|------|--------------------------------------------------------------|------------|");
         }
+        println!("|------|--------------------------------------------------------------|------------|");
     }
 
-    if !found {
-        println!("Tidak ada catatan yang cocok dengan kata kunci '{}'.", keyword);
+    if results.is_empty() {
+        println!("Tidak ada catatan yang cocok dengan kata kunci '{}'.", input);
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+fn ranked_search(conn: &Connection, key: &Key<Aes256Gcm>, input: &str, options: SearchOptions) -> anyhow::Result<()> {
+    let Some(filter) = read_filter(input) else {
+        return Ok(());
+    };
+    let notes: Vec<Note> = load_notes(conn, key)?
+        .into_iter()
+        .filter(|n| filter.matches_fields(n))
+        .collect();
+
+    // Tanpa teks, semua catatan yang lolos filter ditampilkan dengan skor 0
+    let query = parse_query(&filter.text).map(|q| if options.stemming { stem_query(q) } else { q });
+    let scores = match &query {
+        Some(query) => {
+            let documents: Vec<Document> = notes.iter()
+                .map(|note| Document { id: note.id, words: search_words(&note.text(), options.stemming) })
+                .collect();
+            rank(query, &documents)
+        }
+        None if filter.has_fields() => notes.iter().map(|n| (n.id, 0.0)).collect(),
+        None => {
+            println!("Query pencarian kosong.");
+            return Ok(());
+        }
+    };
+
+    let mut results: Vec<(&Note, f64)> = scores.iter()
+        .filter_map(|(id, score)| notes.iter().find(|n| n.id == *id).map(|n| (n, *score)))
+        .collect();
+    let priorities = Priorities::load(conn, key)?;
+    sort_results(&mut results, &priorities, options.sort, |r| r.0);
+
+    println!("\nHasil Pencarian untuk '{}':", input);
+    println!("| {:<4} | {:<60} | {:<10} | {:<6} |", "ID", "Note", "Priority", "Skor");
+    println!("|------|--------------------------------------------------------------|------------|--------|");
+
+    for (note, score) in &results {
+        let text = table_text(&note.text());
+        let ranges = query.as_ref().map_or_else(Vec::new, |q| match_ranges(q, &search_spans(&text, options.stemming)));
+        for (i, line) in render_note(&text, &ranges, options.snippet).iter().enumerate() {
+            if i == 0 {
+                println!("| {:<4} | {} | {} | {:>6.2} |", note.id, line, priorities.label(&note.priority, 10), score);
+            } else {
+                println!("| {:<4} | {} | {:<10} | {:<6} |", "", line, "", "");
+            }
+        }
+        println!("|------|--------------------------------------------------------------|------------|--------|");
+    }
+
+    if results.is_empty() {
+        println!("Tidak ada catatan yang cocok dengan query '{}'.", input);
+    }
+    Ok(())
+}
+
+fn regex_search(conn: &Connection, key: &Key<Aes256Gcm>, input: &str, options: SearchOptions) -> anyhow::Result<()> {
+    // Pola tidak dipecah per kata, jadi spasi dan token seperti is:\w+ tetap bagian dari pola
+    let (pattern, filters) = split_regex_query(input);
+    let Some(filter) = read_filter(filters) else {
+        return Ok(());
+    };
+    // Pola yang hanya berisi flag (?i) dianggap kosong, kalau tidak semua posisi akan cocok
+    let pattern = if pattern.strip_prefix("(?i)").unwrap_or(pattern).is_empty() { "" } else { pattern };
+    if pattern.is_empty() && !filter.has_fields() && filter.text.is_empty() {
+        println!("Pola regex kosong.");
+        return Ok(());
+    }
+    let regex = match RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+    {
+        Ok(regex) => regex,
+        Err(e) => {
+            println!("Pola regex tidak valid: {}", e);
+            return Ok(());
+        }
+    };
+
+    let notes = load_notes(conn, key)?;
+    let mut results: Vec<_> = notes.iter()
+        .filter(|note| filter.matches(note))
+        .map(|note| {
+            // Tanpa NOTE_MARKDOWN pola dicocokkan dengan teks mentah, jadi penanda seperti ** atau [[12]] tetap bisa dicari;
+            // jika aktif, dicocokkan dengan teks bersih yang ditampilkan agar posisi sorotan tetap benar
+            let text = if markdown::enabled() { markdown::strip(&note.text()) } else { note.text() };
+            let ranges = if pattern.is_empty() {
+                Vec::new()
+            } else {
+                regex.find_iter(&text).map(|m| (m.start(), m.end())).collect::<Vec<_>>()
+            };
+            (note, text, ranges)
+        })
+        .filter(|(_, _, ranges)| pattern.is_empty() || !ranges.is_empty())
+        .collect();
+    let priorities = Priorities::load(conn, key)?;
+    sort_results(&mut results, &priorities, options.sort, |r| r.0);
+
+    println!("\nHasil Pencarian Regex untuk '{}':", display_query("4", input));
+    println!("| {:<4} | {:<60} | {:<10} |", "ID", "Note", "Priority");
+    println!("|------|--------------------------------------------------------------|------------|");
+
+    for (note, text, ranges) in &results {
+        for (i, line) in render_note(text, ranges, options.snippet).iter().enumerate() {
+            if i == 0 {
+                println!("| {:<4} | {} | {} |", note.id, line, priorities.label(&note.priority, 10));
+            } else {
+                println!("| {:<4} | {} | {:<10} |", "", line, "");
+            }
+        }
+        println!("|------|--------------------------------------------------------------|------------|");
+    }
+
+    if results.is_empty() {
+        println!("Tidak ada catatan yang cocok dengan pola '{}'.", display_query("4", input));
+    }
+    Ok(())
+}
+
+// Dipakai dari menu maupun dari perintah `fuzzy` di CLI
+pub fn print_fuzzy_results(conn: &Connection, key: &Key<Aes256Gcm>, input: &str, options: SearchOptions) -> anyhow::Result<()> {
+    let Some(filter) = read_filter(input) else {
+        return Ok(());
+    };
+    let notes: Vec<Note> = load_notes(conn, key)?
+        .into_iter()
+        .filter(|n| filter.matches_fields(n))
+        .collect();
+    let documents: Vec<Document> = notes.iter()
+        .map(|note| Document { id: note.id, words: search_words(&note.text(), options.stemming) })
+        .collect();
+    let query_text = if options.stemming {
+        search_words(&filter.text, true).join(" ")
+    } else {
+        filter.text.clone()
+    };
+    let matches = fuzzy_search(&query_text, &documents);
+    let mut results: Vec<(&Note, &FuzzyMatch)> = matches.iter()
+        .filter_map(|m| notes.iter().find(|n| n.id == m.id).map(|n| (n, m)))
+        .collect();
+    let priorities = Priorities::load(conn, key)?;
+    sort_results(&mut results, &priorities, options.sort, |r| r.0);
+
+    println!("\nHasil Pencarian Fuzzy untuk '{}':", input);
+    println!("| {:<4} | {:<60} | {:<10} | {:<6} | {:<20} |", "ID", "Note", "Priority", "Skor", "Kata Cocok");
+    println!("|------|--------------------------------------------------------------|------------|--------|----------------------|");
+
+    for (note, result) in &results {
+        let matched: Vec<String> = result.matched.iter()
+            .map(|(query_word, word)| if query_word == word {
+                word.clone()
+            } else {
+                format!("{} -> {}", query_word, word)
+            })
+            .collect();
+        let targets: Vec<&String> = result.matched.iter().map(|(_, word)| word).collect();
+        let text = table_text(&note.text());
+        let lines = render_note(&text, &word_ranges(&text, &targets, options.stemming), options.snippet);
+        for (i, line) in lines.iter().enumerate() {
+            let matched_word = matched.get(i).map_or("", String::as_str);
+            if i == 0 {
+                println!("| {:<4} | {} | {} | {:>6.2} | {:<20} |", note.id, line, priorities.label(&note.priority, 10), result.score, matched_word);
+            } else {
+                println!("| {:<4} | {} | {:<10} | {:<6} | {:<20} |", "", line, "", "", matched_word);
+            }
+        }
+        for matched_word in matched.iter().skip(lines.len()) {
+            println!("| {:<4} | {:<60} | {:<10} | {:<6} | {:<20} |", "", "", "", "", matched_word);
+        }
+        println!("|------|--------------------------------------------------------------|------------|--------|----------------------|");
+    }
+
+    if results.is_empty() {
+        println!("Tidak ada catatan yang mirip dengan '{}'.", input);
+    }
+    Ok(())
+}
+
+// Mendekripsi semua catatan yang bukan rahasia untuk pencarian di memori
+fn load_notes(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<Vec<Note>> {
+    let mut stmt = conn.prepare(
+        &format!("SELECT {} FROM notes WHERE secret = 0 AND deletedAt IS NULL ORDER BY id ASC", NOTE_COLUMNS)
+    ).context("Failed to prepare statement")?;
+    let note_iter = stmt.query_and_then([], |row| note_from_row(row, key))
+        .context("Failed to query notes")?;
+    let mut notes = note_iter.collect::<anyhow::Result<Vec<Note>>>()?;
+    attach_details(conn, key, &mut notes)?;
+    Ok(notes)
+}
+
+// Hanya mendekripsi catatan dengan ID tertentu, misalnya hasil indeks
+fn load_notes_by_id(conn: &Connection, key: &Key<Aes256Gcm>, ids: &[i32]) -> anyhow::Result<Vec<Note>> {
+    let mut stmt = conn.prepare(
+        &format!("SELECT {} FROM notes WHERE id = ?1 AND secret = 0 AND deletedAt IS NULL", NOTE_COLUMNS)
+    ).context("Failed to prepare statement")?;
+    let mut notes = Vec::new();
+    for id in ids {
+        let note_iter = stmt.query_and_then(params![id], |row| note_from_row(row, key))
+            .context("Failed to query notes")?;
+        for note in note_iter {
+            notes.push(note?);
+        }
+    }
+    attach_details(conn, key, &mut notes)?;
+    Ok(notes)
+}
+
+// Tag dan progres checklist dibutuhkan untuk filter `tag:` dan `todo:` pada semua mode pencarian
+fn attach_details(conn: &Connection, key: &Key<Aes256Gcm>, notes: &mut [Note]) -> anyhow::Result<()> {
+    let mut tag_map = note_tag_map(conn, key)?;
+    let progress = checklist_map(conn)?;
+    for note in notes {
+        note.tags = tag_map.remove(&note.id).unwrap_or_default();
+        note.checklist = progress.get(&note.id).copied().unwrap_or_default();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regex_pattern_is_kept_verbatim() {
+        for pattern in ["foo  bar", "a\tb", "is:\\w+ todo:\\s*\\w+", "(?i)prio:tinggi"] {
+            assert_eq!(split_regex_query(&regex_query(pattern, "")), (pattern, ""));
+        }
+    }
+
+    #[test]
+    fn regex_filters_are_split_from_the_pattern() {
+        let query = regex_query("(?i)tag:\\w+  x", "prio:tinggi is:archived");
+        assert_eq!(split_regex_query(&query), ("(?i)tag:\\w+  x", "prio:tinggi is:archived"));
+        assert_eq!(display_query("4", &query), "(?i)tag:\\w+  x [prio:tinggi is:archived]");
+        assert_eq!(display_query("1", "rapat prio:tinggi"), "rapat prio:tinggi");
+    }
+}
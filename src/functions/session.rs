@@ -0,0 +1,75 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use zeroize::Zeroize;
+use crate::functions::utils::{decrypt_data, encrypt_data, read_input_blocking};
+
+const KEY_CHECK: &str = "notes_app_rust";
+
+// Memastikan kunci cocok dengan database; database baru menyimpan penanda kunci
+pub fn verify_key(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<bool> {
+    let key_check: Option<Vec<u8>> = conn.query_row(
+        "SELECT value FROM metadata WHERE key = 'key_check'",
+        [],
+        |row| row.get(0),
+    ).optional().context("Failed to read key check")?;
+
+    match key_check {
+        Some(encrypted) => Ok(decrypt_data(&encrypted, key).is_ok_and(|s| s == KEY_CHECK)),
+        None => {
+            // Database lama tanpa penanda: cocokkan dengan salah satu catatan yang ada
+            let sample: Option<Vec<u8>> = conn.query_row(
+                "SELECT priority FROM notes LIMIT 1",
+                [],
+                |row| row.get(0),
+            ).optional().context("Failed to read note")?;
+            if sample.is_some_and(|encrypted| decrypt_data(&encrypted, key).is_err()) {
+                return Ok(false);
+            }
+            let encrypted = encrypt_data(KEY_CHECK, key)?;
+            conn.execute(
+                "INSERT INTO metadata (key, value) VALUES ('key_check', ?1)",
+                params![encrypted],
+            ).context("Failed to store key check")?;
+            Ok(true)
+        }
+    }
+}
+
+// Mengosongkan layar dan menghapus kunci dari memori
+pub fn lock_session(key: &mut Option<Key<Aes256Gcm>>) {
+    if let Some(mut k) = key.take() {
+        k.as_mut_slice().zeroize();
+    }
+    print!("\x1B[2J\x1B[H");
+    println!("Sesi terkunci karena tidak ada aktivitas.");
+}
+
+// Meminta ENCRYPTION_KEY lagi sampai cocok dengan database
+pub fn unlock_session(conn: &Connection) -> anyhow::Result<Option<Key<Aes256Gcm>>> {
+    loop {
+        println!("Masukkan ENCRYPTION_KEY untuk membuka kunci (kosongkan untuk keluar): ");
+        let mut input = String::new();
+        if read_input_blocking(&mut input)? == 0 {
+            return Ok(None);
+        }
+        let mut passphrase = input.trim_end_matches(['\r', '\n']).to_string();
+        input.zeroize();
+        if passphrase.is_empty() {
+            return Ok(None);
+        }
+        if passphrase.len() != 32 {
+            passphrase.zeroize();
+            println!("ENCRYPTION_KEY harus tepat 32 byte.");
+            continue;
+        }
+        let mut key = *Key::<Aes256Gcm>::from_slice(passphrase.as_bytes());
+        passphrase.zeroize();
+        if verify_key(conn, &key)? {
+            print!("\x1B[2J\x1B[H");
+            return Ok(Some(key));
+        }
+        key.as_mut_slice().zeroize();
+        println!("ENCRYPTION_KEY salah!");
+    }
+}
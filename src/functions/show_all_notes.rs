@@ -2,62 +2,67 @@ use rusqlite::{Connection, params};
 use textwrap::wrap;
 use aes_gcm::{Aes256Gcm, Key};
 use anyhow::Context;
-use chrono::{DateTime, Utc};
-use crate::functions::utils::{Note, decrypt_data};
+use crate::functions::query_filter::{NoteFilter, parse_filter};
+use crate::functions::checklist::checklist_map;
+use crate::functions::priorities::Priorities;
+use crate::functions::tags::note_tag_map;
+use crate::functions::utils::{NOTE_COLUMNS, note_from_row, read_input};
 
 pub fn show_all_notes(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
-    let query = "SELECT id, note, priority, createdAt, modifiedAt FROM notes ORDER BY id ASC";
+    print_all_notes(conn, key, None)
+}
 
-    let mut stmt = conn.prepare(query).context("Failed to prepare statement")?;
+// Daftar semua catatan yang dibatasi query seperti `deploy prio:tinggi modified:<7d`
+pub fn show_filtered_notes(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    println!("Masukkan filter (contoh: deploy prio:tinggi tag:kerja folder:kerja todo:open is:pinned created:>=2025-03-01 modified:<7d): ");
+    let mut input = String::new();
+    read_input(&mut input)?;
+    match parse_filter(input.trim()) {
+        Ok(filter) => print_all_notes(conn, key, Some(&filter)),
+        Err(e) => {
+            println!("{}", e);
+            Ok(())
+        }
+    }
+}
+
+pub fn print_all_notes(conn: &Connection, key: &Key<Aes256Gcm>, filter: Option<&NoteFilter>) -> anyhow::Result<()> {
+    // Tanpa filter, catatan arsip tetap disembunyikan lewat filter default
+    let default_filter = NoteFilter::default();
+    let filter = filter.unwrap_or(&default_filter);
+    let query = format!("SELECT {} FROM notes WHERE deletedAt IS NULL ORDER BY pinned DESC, id ASC", NOTE_COLUMNS);
+
+    let mut stmt = conn.prepare(&query).context("Failed to prepare statement")?;
 
     let note_iter = stmt.query_and_then(
         params![],
-        |row| -> anyhow::Result<Note> {
-            let encrypted_note: Vec<u8> = row.get(1)
-                .context("Failed to get note from row")?;
-            let encrypted_priority: Vec<u8> = row.get(2)
-                .context("Failed to get priority from row")?;
-            let note = decrypt_data(&encrypted_note, key)
-                .context("Failed to decrypt note")?;
-            let priority = decrypt_data(&encrypted_priority, key)
-                .context("Failed to decrypt priority")?;
-            let created_at_str: String = row.get(3)
-                .context("Failed to get createdAt from row")?;
-            let modified_at_str: Option<String> = row.get(4)
-                .context("Failed to get modifiedAt from row")?;
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .context("Failed to parse createdAt")?;
-            let modified_at = modified_at_str.map(|s| {
-                DateTime::parse_from_rfc3339(&s)
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .context("Failed to parse modifiedAt")
-            }).transpose()?;
-            Ok(Note {
-                id: row.get(0).context("Failed to get id from row")?,
-                note,
-                priority,
-                created_at,
-                modified_at,
-            })
-        }
+        |row| note_from_row(row, key),
     ).context("Failed to query all notes")?;
 
     println!("\nDaftar Semua Catatan (Urut berdasarkan ID):");
     println!("| {:<4} | {:<60} | {:<10} | {:<19} | {:<19} |", 
-             "ID", "Note", "Priority", "Created At", "Modified At");
+             "ID", "Judul", "Priority", "Created At", "Modified At");
     println!("|------|--------------------------------------------------------------|------------|---------------------|---------------------|");
 
+    let mut tag_map = note_tag_map(conn, key)?;
+    let progress = checklist_map(conn)?;
+    let priorities = Priorities::load(conn, key)?;
     for note in note_iter {
-        let note = note?;
-        let wrapped_note = wrap(&note.note, 60);
+        let mut note = note?;
+        note.tags = tag_map.remove(&note.id).unwrap_or_default();
+        note.checklist = progress.get(&note.id).copied().unwrap_or_default();
+        if !filter.matches(&note) {
+            continue;
+        }
+        let label = note.list_label();
+        let wrapped_note = wrap(&label, 60);
         for (i, line) in wrapped_note.iter().enumerate() {
             if i == 0 {
                 println!(
-                    "| {:<4} | {:<60} | {:<10} | {:<19} | {:<19} |",
+                    "| {:<4} | {:<60} | {} | {:<19} | {:<19} |",
                     note.id,
                     line,
-                    note.priority,
+                    priorities.label(&note.priority, 10),
                     note.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
                     note.modified_at.map_or("".to_string(), |dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
                 );
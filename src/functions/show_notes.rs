@@ -2,11 +2,21 @@ use rusqlite::{Connection, params};
 use textwrap::wrap;
 use aes_gcm::{Aes256Gcm, Key};
 use anyhow::Context;
-use chrono::{DateTime, Utc};
 use std::env;
-use crate::functions::utils::{Note, decrypt_data};
+use crate::functions::query_filter::parse_filter;
+use crate::functions::saved_searches::pinned_search;
+use crate::functions::checklist::checklist_map;
+use crate::functions::priorities::Priorities;
+use crate::functions::tags::note_tag_map;
+use crate::functions::utils::{NOTE_COLUMNS, note_from_row};
 
 pub fn show_notes(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    // Pencarian tersimpan yang dijadikan default menggantikan daftar terbaru
+    if let Some(search) = pinned_search(conn, key)? {
+        println!("\nTampilan Default: {}", search.name);
+        return search.run(conn, key);
+    }
+
     let limit = env::var("SHOW_LIMIT")
         .unwrap_or_else(|_| "10".to_string())
         .parse::<i64>()
@@ -26,56 +36,57 @@ pub fn show_notes(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()>
         }
     };
 
+    // SHOW_FILTER memakai sintaks query yang sama dengan pencarian
+    let filter = match env::var("SHOW_FILTER") {
+        Ok(raw) if !raw.trim().is_empty() => match parse_filter(&raw) {
+            Ok(filter) => Some(filter),
+            Err(e) => {
+                println!("SHOW_FILTER tidak valid di .env: {}", e);
+                None
+            }
+        },
+        _ => None,
+    };
+
     let query = format!(
-        "SELECT id, note, priority, createdAt, modifiedAt FROM notes ORDER BY {} LIMIT ?1",
-        order_column
+        "SELECT {} FROM notes WHERE archived = 0 AND deletedAt IS NULL ORDER BY pinned DESC, {} LIMIT ?1",
+        NOTE_COLUMNS, order_column
     );
 
     let mut stmt = conn.prepare(&query).context("Failed to prepare statement")?;
 
     let note_iter = stmt.query_and_then(
-        params![limit],
-        |row| -> anyhow::Result<Note> {
-            let encrypted_note: Vec<u8> = row.get(1)
-                .context("Failed to get note from row")?;
-            let encrypted_priority: Vec<u8> = row.get(2)
-                .context("Failed to get priority from row")?;
-            let note = decrypt_data(&encrypted_note, key)
-                .context("Failed to decrypt note")?;
-            let priority = decrypt_data(&encrypted_priority, key)
-                .context("Failed to decrypt priority")?;
-            let created_at_str: String = row.get(3)
-                .context("Failed to get createdAt from row")?;
-            let modified_at_str: Option<String> = row.get(4)
-                .context("Failed to get modifiedAt from row")?;
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .context("Failed to parse createdAt")?;
-            let modified_at = modified_at_str.map(|s| {
-                DateTime::parse_from_rfc3339(&s)
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .context("Failed to parse modifiedAt")
-            }).transpose()?;
-            Ok(Note {
-                id: row.get(0).context("Failed to get id from row")?,
-                note,
-                priority,
-                created_at,
-                modified_at,
-            })
-        }
+        // Dengan filter, limit diterapkan setelah penyaringan
+        params![if filter.is_some() { -1 } else { limit }],
+        |row| note_from_row(row, key),
     ).context("Failed to query notes")?;
 
     println!("\nDaftar Catatan (Limit: {}, Order By: {}):", limit, order_by);
-    println!("| {:<4} | {:<60} | {:<10} |", "ID", "Note", "Priority");
+    println!("| {:<4} | {:<60} | {:<10} |", "ID", "Judul", "Priority");
     println!("|------|--------------------------------------------------------------|------------|");
 
+    let mut tag_map = note_tag_map(conn, key)?;
+    let progress = checklist_map(conn)?;
+    let priorities = Priorities::load(conn, key)?;
+    let mut shown = 0;
     for note in note_iter {
-        let note = note?;
-        let wrapped_note = wrap(&note.note, 60);
+        let mut note = note?;
+        note.tags = tag_map.remove(&note.id).unwrap_or_default();
+        note.checklist = progress.get(&note.id).copied().unwrap_or_default();
+        if let Some(filter) = &filter {
+            if !filter.matches(&note) {
+                continue;
+            }
+            if shown >= limit {
+                break;
+            }
+        }
+        shown += 1;
+        let label = note.list_label();
+        let wrapped_note = wrap(&label, 60);
         for (i, line) in wrapped_note.iter().enumerate() {
             if i == 0 {
-                println!("| {:<4} | {:<60} | {:<10} |", note.id, line, note.priority);
+                println!("| {:<4} | {:<60} | {} |", note.id, line, priorities.label(&note.priority, 10));
             } else {
                 println!("| {:<4} | {:<60} | {:<10} |", "", line, "");
             }
@@ -0,0 +1,295 @@
+use std::env;
+
+// Bahasa stemming diatur lewat SEARCH_LANGUAGE=id (default) atau en
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Indonesian,
+    English,
+}
+
+pub fn language() -> Language {
+    match env::var("SEARCH_LANGUAGE").map(|v| v.trim().to_lowercase()).as_deref() {
+        Ok("en") | Ok("english") | Ok("inggris") => Language::English,
+        _ => Language::Indonesian,
+    }
+}
+
+impl Language {
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::Indonesian => "id",
+            Language::English => "en",
+        }
+    }
+}
+
+// Kata harus sudah dinormalisasi (huruf kecil) sebelum di-stem
+pub fn stem(word: &str) -> String {
+    match language() {
+        Language::Indonesian => stem_indonesian(word),
+        Language::English => stem_english(word),
+    }
+}
+
+fn char_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+fn starts_with_vowel(s: &str) -> bool {
+    s.chars().next().is_some_and(is_vowel)
+}
+
+// --- Bahasa Indonesia: penghapusan imbuhan ala Nazief-Adriani tanpa kamus kata dasar ---
+
+const MIN_ROOT: usize = 4;
+const PARTICLES: [&str; 4] = ["lah", "kah", "tah", "pun"];
+const POSSESSIVES: [&str; 3] = ["nya", "ku", "mu"];
+const DERIVATIONAL_SUFFIXES: [&str; 3] = ["kan", "an", "i"];
+// Pasangan awalan-akhiran yang tidak mungkin muncul bersama
+const DISALLOWED_CONFIXES: [(&str, &str); 8] = [
+    ("be", "i"), ("di", "an"), ("ke", "i"), ("ke", "kan"),
+    ("me", "an"), ("se", "i"), ("se", "kan"), ("te", "an"),
+];
+// Kata dasar umum yang bentuknya mirip kata berimbuhan
+const ROOT_EXCEPTIONS: [&str; 10] = [
+    "sekolah", "masalah", "kepala", "keluarga", "sepeda",
+    "senang", "dengan", "perlu", "pernah", "sebab",
+];
+
+fn strip_any_suffix<'a>(word: &str, suffixes: &[&'a str]) -> Option<(String, &'a str)> {
+    suffixes.iter().find_map(|suffix| {
+        word.strip_suffix(suffix)
+            .filter(|rest| char_len(rest) >= MIN_ROOT)
+            .map(|rest| (rest.to_string(), *suffix))
+    })
+}
+
+fn remove_inflection(word: &str) -> String {
+    let word = strip_any_suffix(word, &PARTICLES).map_or_else(|| word.to_string(), |(w, _)| w);
+    strip_any_suffix(&word, &POSSESSIVES).map_or(word, |(w, _)| w)
+}
+
+// Melepas satu awalan beserta aturan peluluhan (meny- -> s, mem- -> p, men- -> t)
+fn remove_prefix_once(word: &str) -> Option<(String, &'static str)> {
+    if word == "belajar" || word == "pelajar" {
+        return Some(("ajar".to_string(), if word.starts_with('b') { "be" } else { "pe" }));
+    }
+    for (prefix, kind) in [("di", "di"), ("ke", "ke"), ("se", "se"), ("ter", "te"), ("per", "pe")] {
+        if let Some(rest) = word.strip_prefix(prefix) {
+            return Some((rest.to_string(), kind));
+        }
+    }
+    if let Some(rest) = word.strip_prefix("ber") {
+        return Some((rest.to_string(), "be"));
+    }
+    // be- + K + er, misalnya bekerja -> kerja
+    if let Some(rest) = word.strip_prefix("be") {
+        let mut chars = rest.chars();
+        if chars.next().is_some_and(|c| !is_vowel(c)) && chars.as_str().starts_with("er") {
+            return Some((rest.to_string(), "be"));
+        }
+    }
+    for (me, kind) in [("me", "me"), ("pe", "pe")] {
+        let Some(rest) = word.strip_prefix(me) else {
+            continue;
+        };
+        let recoded = if let Some(r) = rest.strip_prefix("ny").filter(|r| starts_with_vowel(r)) {
+            format!("s{}", r)
+        } else if let Some(r) = rest.strip_prefix("nge").filter(|r| !starts_with_vowel(r) && char_len(r) >= 3) {
+            r.to_string()
+        } else if let Some(r) = rest.strip_prefix("ng") {
+            r.to_string()
+        } else if let Some(r) = rest.strip_prefix('m') {
+            if starts_with_vowel(r) { format!("p{}", r) } else { r.to_string() }
+        } else if let Some(r) = rest.strip_prefix('n') {
+            if starts_with_vowel(r) { format!("t{}", r) } else { r.to_string() }
+        } else {
+            rest.to_string()
+        };
+        return Some((recoded, kind));
+    }
+    None
+}
+
+// Hingga tiga awalan dilepas selama sisa kata tidak terlalu pendek
+fn remove_prefixes(word: &str, suffix: Option<&str>) -> String {
+    let mut current = word.to_string();
+    for round in 0..3 {
+        let Some((rest, kind)) = remove_prefix_once(&current) else {
+            break;
+        };
+        if char_len(&rest) < MIN_ROOT {
+            break;
+        }
+        if round == 0 && suffix.is_some_and(|s| DISALLOWED_CONFIXES.contains(&(kind, s))) {
+            break;
+        }
+        current = rest;
+    }
+    current
+}
+
+pub fn stem_indonesian(word: &str) -> String {
+    if char_len(word) <= MIN_ROOT || ROOT_EXCEPTIONS.contains(&word) {
+        return word.to_string();
+    }
+    let base = remove_inflection(word);
+    let prefix_only = remove_prefixes(&base, None);
+    match strip_any_suffix(&base, &DERIVATIONAL_SUFFIXES) {
+        Some((without_suffix, suffix)) => {
+            let both = remove_prefixes(&without_suffix, Some(suffix));
+            // Jika awalan hanya bisa dilepas dengan akhiran dipertahankan, akhiran dikembalikan
+            if both == without_suffix && prefix_only != base {
+                prefix_only
+            } else {
+                both
+            }
+        }
+        None => prefix_only,
+    }
+}
+
+// --- Bahasa Inggris: stemmer ringan berdasarkan langkah awal Porter ---
+
+fn has_vowel(s: &str) -> bool {
+    s.chars().any(|c| is_vowel(c) || c == 'y')
+}
+
+// Pola konsonan-vokal-konsonan di akhir kata pendek, misalnya hop -> hope
+fn ends_cvc(s: &str) -> bool {
+    let chars: Vec<char> = s.chars().collect();
+    let n = chars.len();
+    n >= 3
+        && !is_vowel(chars[n - 3])
+        && is_vowel(chars[n - 2])
+        && !is_vowel(chars[n - 1])
+        && !matches!(chars[n - 1], 'w' | 'x' | 'y')
+}
+
+pub fn stem_english(word: &str) -> String {
+    if char_len(word) <= 3 {
+        return word.to_string();
+    }
+    let mut w = word.to_string();
+
+    if let Some(rest) = w.strip_suffix("sses") {
+        w = format!("{}ss", rest);
+    } else if let Some(rest) = w.strip_suffix("ies") {
+        w = format!("{}y", rest);
+    } else if w.ends_with('s') && !w.ends_with("ss") && !w.ends_with("us") {
+        w.pop();
+    }
+
+    if let Some(rest) = w.strip_suffix("eed") {
+        w = format!("{}ee", rest);
+    } else if let Some(rest) = w.strip_suffix("ing").or_else(|| w.strip_suffix("ed"))
+        && has_vowel(rest)
+        && char_len(rest) >= 2
+    {
+        let mut stem = rest.to_string();
+        let chars: Vec<char> = stem.chars().collect();
+        let n = chars.len();
+        if stem.ends_with("at") || stem.ends_with("bl") || stem.ends_with("iz") {
+            stem.push('e');
+        } else if n >= 2 && chars[n - 1] == chars[n - 2] && !is_vowel(chars[n - 1]) && !matches!(chars[n - 1], 'l' | 's' | 'z') {
+            stem.pop();
+        } else if n == 3 && ends_cvc(&stem) {
+            stem.push('e');
+        }
+        w = stem;
+    }
+
+    for (suffix, replacement) in [
+        ("ational", "ate"), ("tional", "tion"), ("ization", "ize"), ("fulness", "ful"),
+        ("ousness", "ous"), ("iveness", "ive"), ("ness", ""), ("ment", ""), ("ly", ""),
+    ] {
+        if let Some(rest) = w.strip_suffix(suffix) {
+            if char_len(rest) >= 3 {
+                w = format!("{}{}", rest, replacement);
+            }
+            break;
+        }
+    }
+    w
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_stems(stemmer: fn(&str) -> String, cases: &[(&str, &str)]) {
+        for (word, expected) in cases {
+            assert_eq!(stemmer(word), *expected, "stem dari '{}'", word);
+        }
+    }
+
+    #[test]
+    fn indonesian_removes_suffixes_and_inflections() {
+        assert_stems(stem_indonesian, &[
+            ("makanan", "makan"),
+            ("bukunya", "buku"),
+            ("rumahku", "rumah"),
+            ("bacalah", "baca"),
+            ("apakah", "apakah"),
+        ]);
+    }
+
+    #[test]
+    fn indonesian_removes_prefixes_with_recoding() {
+        assert_stems(stem_indonesian, &[
+            ("dimakan", "makan"),
+            ("menyapu", "sapu"),
+            ("menulis", "tulis"),
+            ("memukul", "pukul"),
+            ("mengambil", "ambil"),
+            ("berlari", "lari"),
+            ("bekerja", "kerja"),
+            ("belajar", "ajar"),
+            ("pelajar", "ajar"),
+            ("perjalanan", "jalan"),
+        ]);
+    }
+
+    #[test]
+    fn indonesian_keeps_short_words_and_exceptions() {
+        assert_stems(stem_indonesian, &[
+            ("kue", "kue"),
+            ("makan", "makan"),
+            ("sekolah", "sekolah"),
+            ("keluarga", "keluarga"),
+            ("dengan", "dengan"),
+        ]);
+    }
+
+    #[test]
+    fn english_removes_plurals_and_verb_endings() {
+        assert_stems(stem_english, &[
+            ("cats", "cat"),
+            ("ponies", "pony"),
+            ("caresses", "caress"),
+            ("status", "status"),
+            ("agreed", "agree"),
+            ("running", "run"),
+            ("hopping", "hop"),
+            ("hoped", "hope"),
+            ("testing", "test"),
+            ("fixed", "fix"),
+            ("bus", "bus"),
+        ]);
+    }
+
+    #[test]
+    fn english_maps_derivational_suffixes() {
+        assert_stems(stem_english, &[
+            ("relational", "relate"),
+            ("conditional", "condition"),
+            ("quickly", "quick"),
+            ("payment", "pay"),
+            ("happiness", "happi"),
+        ]);
+    }
+}
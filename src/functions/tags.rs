@@ -0,0 +1,223 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use hmac::Mac;
+use std::collections::HashMap;
+use crate::functions::utils::{decrypt_data, derive_mac, encrypt_data, read_input};
+use crate::functions::validation::{print_errors, validate_tag};
+
+// Nama tag dienkripsi; HMAC dari nama yang dinormalisasi dipakai untuk mencari tag yang sama
+fn tag_hash(key: &Key<Aes256Gcm>, name: &str) -> anyhow::Result<Vec<u8>> {
+    let mut mac = derive_mac(key, "notes_app_rust tag names")?;
+    mac.update(normalize_tag(name).as_bytes());
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+// Tag dibandingkan tanpa membedakan huruf besar/kecil dan tanpa awalan #
+pub fn normalize_tag(name: &str) -> String {
+    name.trim().trim_start_matches('#').to_lowercase()
+}
+
+// Memecah input seperti "kerja, #rumah" menjadi daftar tag; tag tidak valid dilaporkan dan dilewati
+pub fn parse_tag_list(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let mut errors = Vec::new();
+    for raw in input.split(',').filter(|t| !t.trim().is_empty()) {
+        match validate_tag(raw) {
+            Ok(tag) if !tags.iter().any(|t| normalize_tag(t) == normalize_tag(&tag)) => tags.push(tag),
+            Ok(_) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+    if !errors.is_empty() {
+        print_errors(&errors);
+    }
+    tags
+}
+
+fn find_tag(conn: &Connection, key: &Key<Aes256Gcm>, name: &str) -> anyhow::Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM tags WHERE name_hash = ?1",
+        params![tag_hash(key, name)?],
+        |row| row.get(0),
+    ).optional().context("Failed to look up tag")
+}
+
+fn get_or_create_tag(conn: &Connection, key: &Key<Aes256Gcm>, name: &str) -> anyhow::Result<i64> {
+    if let Some(id) = find_tag(conn, key, name)? {
+        return Ok(id);
+    }
+    conn.execute(
+        "INSERT INTO tags (name, name_hash) VALUES (?1, ?2)",
+        params![encrypt_data(name, key)?, tag_hash(key, name)?],
+    ).context("Failed to insert tag")?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn add_tags(conn: &Connection, key: &Key<Aes256Gcm>, note_id: i32, tags: &[String]) -> anyhow::Result<()> {
+    for tag in tags {
+        let tag_id = get_or_create_tag(conn, key, tag)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO note_tags (note_id, tag_id) VALUES (?1, ?2)",
+            params![note_id, tag_id],
+        ).context("Failed to tag note")?;
+    }
+    Ok(())
+}
+
+pub fn remove_tags(conn: &Connection, key: &Key<Aes256Gcm>, note_id: i32, tags: &[String]) -> anyhow::Result<()> {
+    for tag in tags {
+        if let Some(tag_id) = find_tag(conn, key, tag)? {
+            conn.execute(
+                "DELETE FROM note_tags WHERE note_id = ?1 AND tag_id = ?2",
+                params![note_id, tag_id],
+            ).context("Failed to untag note")?;
+        }
+    }
+    remove_unused_tags(conn)
+}
+
+// Dipanggil saat catatan dihapus
+pub fn remove_note_tags(conn: &Connection, note_id: i32) -> anyhow::Result<()> {
+    conn.execute("DELETE FROM note_tags WHERE note_id = ?1", params![note_id])
+        .context("Failed to delete note tags")?;
+    remove_unused_tags(conn)
+}
+
+fn remove_unused_tags(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute("DELETE FROM tags WHERE id NOT IN (SELECT tag_id FROM note_tags)", [])
+        .context("Failed to delete unused tags")?;
+    Ok(())
+}
+
+pub fn note_tags(conn: &Connection, key: &Key<Aes256Gcm>, note_id: i32) -> anyhow::Result<Vec<String>> {
+    Ok(note_tag_map(conn, key)?.remove(&note_id).unwrap_or_default())
+}
+
+// Semua tag per catatan, urut nama, untuk mengisi Note::tags di daftar catatan
+pub fn note_tag_map(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<HashMap<i32, Vec<String>>> {
+    let mut stmt = conn.prepare(
+        "SELECT note_tags.note_id, tags.name FROM note_tags JOIN tags ON tags.id = note_tags.tag_id"
+    ).context("Failed to prepare statement")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, Vec<u8>>(1)?)))
+        .context("Failed to query note tags")?;
+    let mut map: HashMap<i32, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (note_id, encrypted_name) = row?;
+        let name = decrypt_data(&encrypted_name, key).context("Failed to decrypt tag")?;
+        map.entry(note_id).or_default().push(name);
+    }
+    for tags in map.values_mut() {
+        tags.sort_by_key(|t| normalize_tag(t));
+    }
+    Ok(map)
+}
+
+struct TagCount {
+    id: i64,
+    name: String,
+    count: i64,
+}
+
+fn load_tag_counts(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<Vec<TagCount>> {
+    let mut stmt = conn.prepare(
+        "SELECT tags.id, tags.name, COUNT(notes.id) FROM tags
+         LEFT JOIN note_tags ON note_tags.tag_id = tags.id
+         LEFT JOIN notes ON notes.id = note_tags.note_id AND notes.deletedAt IS NULL
+         GROUP BY tags.id"
+    ).context("Failed to prepare statement")?;
+    let rows = stmt.query_and_then([], |row| -> anyhow::Result<TagCount> {
+        let encrypted_name: Vec<u8> = row.get(1).context("Failed to get tag name from row")?;
+        Ok(TagCount {
+            id: row.get(0).context("Failed to get tag id from row")?,
+            name: decrypt_data(&encrypted_name, key).context("Failed to decrypt tag")?,
+            count: row.get(2).context("Failed to get tag count from row")?,
+        })
+    }).context("Failed to query tags")?;
+    let mut tags = rows.collect::<anyhow::Result<Vec<TagCount>>>()?;
+    tags.sort_by_key(|t| normalize_tag(&t.name));
+    Ok(tags)
+}
+
+// Semua catatan bertag `from` dipindah ke `to`, lalu tag `from` dihapus
+fn merge_tags(conn: &Connection, from: i64, to: i64) -> anyhow::Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO note_tags (note_id, tag_id) SELECT note_id, ?2 FROM note_tags WHERE tag_id = ?1",
+        params![from, to],
+    ).context("Failed to merge tags")?;
+    conn.execute("DELETE FROM note_tags WHERE tag_id = ?1", params![from])
+        .context("Failed to merge tags")?;
+    conn.execute("DELETE FROM tags WHERE id = ?1", params![from])
+        .context("Failed to delete merged tag")?;
+    Ok(())
+}
+
+fn read_tag_name(prompt: &str) -> anyhow::Result<String> {
+    println!("{}", prompt);
+    let mut name = String::new();
+    read_input(&mut name)?;
+    Ok(name.trim().to_string())
+}
+
+pub fn tags_menu(conn: &mut Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    let tags = load_tag_counts(conn, key)?;
+    println!("\nDaftar Tag:");
+    println!("| {:<30} | {:<7} |", "Tag", "Catatan");
+    println!("|--------------------------------|---------|");
+    for tag in &tags {
+        println!("| {:<30} | {:>7} |", format!("#{}", tag.name), tag.count);
+    }
+    if tags.is_empty() {
+        println!("Belum ada tag. Tambahkan tag saat menambah atau mengedit catatan.");
+        return Ok(());
+    }
+    println!("\nMenu:");
+    println!("1. Ganti Nama Tag");
+    println!("2. Gabungkan Tag");
+    println!("3. Kembali ke Menu Utama");
+    println!("Pilih opsi (1-3): ");
+
+    let mut choice = String::new();
+    read_input(&mut choice)?;
+    let choice: i32 = choice.trim().parse().unwrap_or(0);
+    if !(1..=2).contains(&choice) {
+        if choice != 3 {
+            println!("Pilihan tidak valid!");
+        }
+        return Ok(());
+    }
+
+    let name = read_tag_name(if choice == 1 { "Masukkan tag yang akan diganti namanya: " } else { "Masukkan tag yang akan digabungkan: " })?;
+    let Some(source) = tags.iter().find(|t| normalize_tag(&t.name) == normalize_tag(&name)) else {
+        println!("Tag '{}' tidak ditemukan!", name);
+        return Ok(());
+    };
+    let target = read_tag_name(if choice == 1 { "Masukkan nama baru: " } else { "Gabungkan ke tag: " })?;
+    let target = match validate_tag(&target) {
+        Ok(target) => target,
+        Err(e) => {
+            print_errors(&[e]);
+            return Ok(());
+        }
+    };
+
+    let tx = conn.transaction().context("Failed to start transaction")?;
+    match (choice, find_tag(&tx, key, &target)?) {
+        // Ganti nama ke tag yang sudah ada sama dengan menggabungkan
+        (_, Some(target_id)) if target_id != source.id => {
+            merge_tags(&tx, source.id, target_id)?;
+            println!("Tag '#{}' digabungkan ke '#{}'.", source.name, target);
+        }
+        (1, _) => {
+            tx.execute(
+                "UPDATE tags SET name = ?1, name_hash = ?2 WHERE id = ?3",
+                params![encrypt_data(&target, key)?, tag_hash(key, &target)?, source.id],
+            ).context("Failed to rename tag")?;
+            println!("Tag '#{}' diganti menjadi '#{}'.", source.name, target);
+        }
+        (_, Some(_)) => println!("Tag sumber dan tujuan sama, tidak ada yang diubah."),
+        (_, None) => println!("Tag tujuan '#{}' tidak ditemukan! Gunakan Ganti Nama Tag.", target),
+    }
+    tx.commit().context("Failed to commit transaction")?;
+    Ok(())
+}
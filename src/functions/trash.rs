@@ -0,0 +1,186 @@
+use rusqlite::{Connection, params};
+use textwrap::wrap;
+use aes_gcm::{Aes256Gcm, Key};
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use std::env;
+use crate::functions::attachments::remove_note_attachments;
+use crate::functions::blind_index::remove_note;
+use crate::functions::checklist::remove_note_items;
+use crate::functions::revisions::remove_note_revisions;
+use crate::functions::tags::remove_note_tags;
+use crate::functions::utils::{NOTE_COLUMNS, Note, note_from_row, read_input};
+
+const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+// Lama catatan disimpan di tempat sampah; 0 berarti tidak pernah dihapus otomatis
+pub fn retention_days() -> i64 {
+    env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|days| *days >= 0 && cutoff(*days).is_some())
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+}
+
+// Batas waktu penghapusan; None jika jumlah hari di luar rentang tanggal yang bisa dihitung
+fn cutoff(days: i64) -> Option<DateTime<Utc>> {
+    Duration::try_days(days).and_then(|age| Utc::now().checked_sub_signed(age))
+}
+
+// Menghapus catatan beserta indeks, tag, checklist, riwayat revisi dan lampirannya secara permanen
+fn purge_note(conn: &Connection, id: i32) -> anyhow::Result<()> {
+    conn.execute("DELETE FROM notes WHERE id = ?1", params![id])
+        .context("Failed to delete note")?;
+    remove_note(conn, id)?;
+    remove_note_tags(conn, id)?;
+    remove_note_items(conn, id)?;
+    remove_note_revisions(conn, id)?;
+    remove_note_attachments(conn, id)?;
+    Ok(())
+}
+
+fn trashed_ids(conn: &Connection) -> anyhow::Result<Vec<(i32, String)>> {
+    let mut stmt = conn.prepare("SELECT id, deletedAt FROM notes WHERE deletedAt IS NOT NULL")
+        .context("Failed to prepare statement")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .context("Failed to query trash")?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+// Dipanggil saat aplikasi dimulai
+pub fn purge_expired(conn: &mut Connection) -> anyhow::Result<()> {
+    let days = retention_days();
+    if days == 0 {
+        return Ok(());
+    }
+    let Some(cutoff) = cutoff(days) else {
+        return Ok(());
+    };
+    let tx = conn.transaction().context("Failed to start transaction")?;
+    let mut purged = 0;
+    for (id, deleted_at) in trashed_ids(&tx)? {
+        let deleted_at = DateTime::parse_from_rfc3339(&deleted_at).context("Failed to parse deletedAt")?;
+        if deleted_at < cutoff {
+            purge_note(&tx, id)?;
+            purged += 1;
+        }
+    }
+    tx.commit().context("Failed to commit transaction")?;
+    if purged > 0 {
+        println!("{} catatan yang lebih dari {} hari di tempat sampah dihapus permanen.", purged, days);
+    }
+    Ok(())
+}
+
+fn load_trash(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<Vec<Note>> {
+    let mut stmt = conn.prepare(
+        &format!("SELECT {} FROM notes WHERE deletedAt IS NOT NULL ORDER BY deletedAt DESC", NOTE_COLUMNS)
+    ).context("Failed to prepare statement")?;
+    let notes = stmt.query_and_then([], |row| note_from_row(row, key))
+        .context("Failed to query trash")?
+        .collect::<anyhow::Result<Vec<Note>>>()?;
+    Ok(notes)
+}
+
+fn print_trash(notes: &[Note]) {
+    let days = retention_days();
+    println!("| {:<4} | {:<60} | {:<19} | {:<10} |", "ID", "Judul", "Dihapus", "Sisa Hari");
+    println!("|------|--------------------------------------------------------------|---------------------|------------|");
+    for note in notes {
+        let deleted_at = note.deleted_at.unwrap_or(note.created_at);
+        let remaining = if days == 0 {
+            "-".to_string()
+        } else {
+            (days - (Utc::now() - deleted_at).num_days()).max(0).to_string()
+        };
+        let label = note.list_label();
+        for (i, line) in wrap(&label, 60).iter().enumerate() {
+            if i == 0 {
+                println!(
+                    "| {:<4} | {:<60} | {:<19} | {:<10} |",
+                    note.id, line, deleted_at.format("%Y-%m-%d %H:%M:%S").to_string(), remaining
+                );
+            } else {
+                println!("| {:<4} | {:<60} | {:<19} | {:<10} |", "", line, "", "");
+            }
+        }
+        println!("|------|--------------------------------------------------------------|---------------------|------------|");
+    }
+}
+
+fn read_trashed_id(notes: &[Note], prompt: &str) -> anyhow::Result<Option<i32>> {
+    println!("{}", prompt);
+    let mut id = String::new();
+    read_input(&mut id)?;
+    let id: i32 = id.trim().parse().unwrap_or(0);
+    if notes.iter().any(|n| n.id == id) {
+        Ok(Some(id))
+    } else {
+        println!("Catatan dengan ID {} tidak ada di tempat sampah!", id);
+        Ok(None)
+    }
+}
+
+fn confirm(prompt: &str) -> anyhow::Result<bool> {
+    println!("{} (y/N): ", prompt);
+    let mut answer = String::new();
+    read_input(&mut answer)?;
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+pub fn trash_menu(conn: &mut Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
+    let notes = load_trash(conn, key)?;
+    println!("\nTempat Sampah ({} catatan):", notes.len());
+    if notes.is_empty() {
+        println!("Tempat sampah kosong.");
+        return Ok(());
+    }
+    print_trash(&notes);
+    println!("\nMenu:");
+    println!("1. Pulihkan Catatan");
+    println!("2. Hapus Permanen");
+    println!("3. Kosongkan Tempat Sampah");
+    println!("4. Kembali ke Menu Utama");
+    println!("Pilih opsi (1-4): ");
+
+    let mut choice = String::new();
+    read_input(&mut choice)?;
+    let choice: i32 = choice.trim().parse().unwrap_or(0);
+
+    match choice {
+        1 => {
+            if let Some(id) = read_trashed_id(&notes, "Masukkan ID catatan yang akan dipulihkan: ")? {
+                conn.execute("UPDATE notes SET deletedAt = NULL WHERE id = ?1", params![id])
+                    .context("Failed to restore note")?;
+                println!("Catatan dengan ID {} berhasil dipulihkan!", id);
+            }
+        }
+        2 => {
+            if let Some(id) = read_trashed_id(&notes, "Masukkan ID catatan yang akan dihapus permanen: ")? {
+                if confirm("Catatan akan dihapus permanen dan tidak bisa dipulihkan. Lanjutkan?")? {
+                    let tx = conn.transaction().context("Failed to start transaction")?;
+                    purge_note(&tx, id)?;
+                    tx.commit().context("Failed to commit transaction")?;
+                    println!("Catatan dengan ID {} dihapus permanen.", id);
+                } else {
+                    println!("Catatan tidak dihapus.");
+                }
+            }
+        }
+        3 => {
+            if confirm(&format!("Hapus permanen {} catatan di tempat sampah?", notes.len()))? {
+                let tx = conn.transaction().context("Failed to start transaction")?;
+                for note in &notes {
+                    purge_note(&tx, note.id)?;
+                }
+                tx.commit().context("Failed to commit transaction")?;
+                println!("Tempat sampah dikosongkan.");
+            } else {
+                println!("Tempat sampah tidak dikosongkan.");
+            }
+        }
+        4 => println!("Kembali ke menu utama."),
+        _ => println!("Pilihan tidak valid!"),
+    }
+    Ok(())
+}
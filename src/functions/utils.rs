@@ -2,17 +2,172 @@ use aes_gcm::{
     aead::{Aead, AeadCore, KeyInit, OsRng},
     Aes256Gcm, Key, Nonce
 };
-use chrono::{DateTime, Utc};
+use anyhow::Context;
+use chrono::{DateTime, Local, Utc};
+use hmac::{Hmac, Mac};
+use rusqlite::Row;
+use sha2::Sha256;
+use zeroize::Zeroize;
+use std::env;
+use std::fs;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use crate::functions::markdown::table_text;
 
 // Struktur untuk merepresentasikan catatan
 pub struct Note {
     pub id: i32,
-    pub note: String,
+    pub title: String,
+    pub body: String,
     pub priority: String,
     pub created_at: DateTime<Utc>,
     pub modified_at: Option<DateTime<Utc>>,
+    pub secret: bool,
+    // Diisi terpisah lewat tags::note_tag_map, kosong jika tidak dimuat
+    pub tags: Vec<String>,
+    // Path folder seperti "kerja/klien", kosong untuk root
+    pub folder: String,
+    pub due_at: Option<DateTime<Utc>>,
+    // Jumlah item checklist (selesai, total), diisi lewat checklist::checklist_map
+    pub checklist: (usize, usize),
+    pub pinned: bool,
+    pub archived: bool,
+    // Waktu catatan dipindahkan ke tempat sampah
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
+impl Note {
+    // Judul dan isi sebagai satu teks, dipakai untuk pencarian dan indeks
+    pub fn text(&self) -> String {
+        full_text(&self.title, &self.body)
+    }
+
+    // Judul di daftar catatan dengan sematan, folder, progres checklist dan tag,
+    // misalnya "[Pin] kerja/ Rapat mingguan [3/5] #rapat"
+    pub fn list_label(&self) -> String {
+        let mut label = if self.secret { SECRET_PLACEHOLDER.to_string() } else { table_text(&self.title) };
+        if !self.folder.is_empty() {
+            label = format!("{}/ {}", self.folder, label);
+        }
+        if self.pinned {
+            label = format!("[Pin] {}", label);
+        }
+        let (done, total) = self.checklist;
+        if total > 0 {
+            label.push_str(&format!(" [{}/{}]", done, total));
+        }
+        for tag in &self.tags {
+            label.push_str(" #");
+            label.push_str(tag);
+        }
+        label
+    }
+}
+
+pub fn full_text(title: &str, body: &str) -> String {
+    if body.is_empty() {
+        title.to_string()
+    } else {
+        format!("{}\n{}", title, body)
+    }
+}
+
+// Kolom yang dibaca oleh note_from_row, dalam urutan yang sama
+pub const NOTE_COLUMNS: &str = "id, note, priority, createdAt, modifiedAt, secret, body, \
+    (SELECT path FROM folders WHERE folders.id = notes.folder_id), dueAt, pinned, archived, deletedAt";
+
+// Kolom `note` menyimpan judul; `body` kosong (NULL) untuk catatan dari versi lama
+pub fn note_from_row(row: &Row, key: &Key<Aes256Gcm>) -> anyhow::Result<Note> {
+    let encrypted_title: Vec<u8> = row.get(1)
+        .context("Failed to get note from row")?;
+    let encrypted_priority: Vec<u8> = row.get(2)
+        .context("Failed to get priority from row")?;
+    let encrypted_body: Option<Vec<u8>> = row.get(6)
+        .context("Failed to get body from row")?;
+    let title = decrypt_data(&encrypted_title, key)
+        .context("Failed to decrypt note")?;
+    let priority = decrypt_data(&encrypted_priority, key)
+        .context("Failed to decrypt priority")?;
+    let body = encrypted_body.map(|b| decrypt_data(&b, key))
+        .transpose()
+        .context("Failed to decrypt body")?
+        .unwrap_or_default();
+    let encrypted_folder: Option<Vec<u8>> = row.get(7)
+        .context("Failed to get folder from row")?;
+    let folder = encrypted_folder.map(|f| decrypt_data(&f, key))
+        .transpose()
+        .context("Failed to decrypt folder")?
+        .unwrap_or_default();
+    let created_at_str: String = row.get(3)
+        .context("Failed to get createdAt from row")?;
+    let modified_at_str: Option<String> = row.get(4)
+        .context("Failed to get modifiedAt from row")?;
+    let created_at = DateTime::parse_from_rfc3339(&created_at_str)
+        .map(|dt| dt.with_timezone(&Utc))
+        .context("Failed to parse createdAt")?;
+    let modified_at = modified_at_str.map(|s| {
+        DateTime::parse_from_rfc3339(&s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .context("Failed to parse modifiedAt")
+    }).transpose()?;
+    let due_at_str: Option<String> = row.get(8)
+        .context("Failed to get dueAt from row")?;
+    let due_at = due_at_str.map(|s| {
+        DateTime::parse_from_rfc3339(&s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .context("Failed to parse dueAt")
+    }).transpose()?;
+    let deleted_at_str: Option<String> = row.get(11)
+        .context("Failed to get deletedAt from row")?;
+    let deleted_at = deleted_at_str.map(|s| {
+        DateTime::parse_from_rfc3339(&s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .context("Failed to parse deletedAt")
+    }).transpose()?;
+    Ok(Note {
+        id: row.get(0).context("Failed to get id from row")?,
+        title,
+        body,
+        priority,
+        created_at,
+        modified_at,
+        secret: row.get(5).context("Failed to get secret from row")?,
+        tags: Vec::new(),
+        folder,
+        due_at,
+        checklist: (0, 0),
+        pinned: row.get(9).context("Failed to get pinned from row")?,
+        archived: row.get(10).context("Failed to get archived from row")?,
+        deleted_at,
+    })
+}
+
+// Tenggat ditampilkan dalam waktu lokal karena dimasukkan dalam waktu lokal
+pub fn format_due(due_at: DateTime<Utc>) -> String {
+    due_at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+}
+
+// Isi kosong disimpan sebagai NULL
+pub fn encrypt_body(body: &str, key: &Key<Aes256Gcm>) -> anyhow::Result<Option<Vec<u8>>> {
+    if body.is_empty() {
+        Ok(None)
+    } else {
+        encrypt_data(body, key).map(Some)
+    }
+}
+
+// Teks pengganti isi catatan rahasia di daftar catatan
+pub const SECRET_PLACEHOLDER: &str = "[Catatan rahasia]";
+
+// Baris penutup isi catatan multi-baris dan perintah untuk membuka editor
+const BODY_TERMINATOR: &str = ".";
+const EDITOR_COMMAND: &str = ":editor";
+
+const PASSPHRASE_ROUNDS: u32 = 100_000;
+
 // Fungsi untuk enkripsi data
 pub fn encrypt_data(data: &str, key: &Key<Aes256Gcm>) -> anyhow::Result<Vec<u8>> {
     let cipher = Aes256Gcm::new(key);
@@ -33,4 +188,211 @@ pub fn decrypt_data(encrypted: &[u8], key: &Key<Aes256Gcm>) -> anyhow::Result<St
         .map_err(|e| anyhow::anyhow!("Decryption failed: {:?}", e))?;
     String::from_utf8(plaintext)
         .map_err(|e| anyhow::anyhow!("UTF-8 conversion failed: {:?}", e))
-}
\ No newline at end of file
+}
+
+// Enkripsi tambahan untuk catatan rahasia, hasilnya hex dari salt + nonce + ciphertext
+pub fn encrypt_with_passphrase(data: &str, passphrase: &str) -> anyhow::Result<String> {
+    let mut salt = [0u8; 16];
+    aes_gcm::aead::rand_core::RngCore::fill_bytes(&mut OsRng, &mut salt);
+    let mut key = derive_passphrase_key(passphrase, &salt);
+    let encrypted = encrypt_data(data, &key);
+    key.as_mut_slice().zeroize();
+    let mut sealed = salt.to_vec();
+    sealed.extend_from_slice(&encrypted?);
+    Ok(hex::encode(sealed))
+}
+
+pub fn decrypt_with_passphrase(sealed: &str, passphrase: &str) -> anyhow::Result<String> {
+    let sealed = hex::decode(sealed)
+        .map_err(|e| anyhow::anyhow!("Invalid secret note data: {:?}", e))?;
+    if sealed.len() < 16 + 12 {
+        anyhow::bail!("Invalid secret note data: too short");
+    }
+    let mut key = derive_passphrase_key(passphrase, &sealed[..16]);
+    let plaintext = decrypt_data(&sealed[16..], &key);
+    key.as_mut_slice().zeroize();
+    plaintext
+}
+
+// HMAC dengan kunci turunan dari kunci database; label memisahkan kegunaannya dan kunci turunan dihapus dari memori
+pub fn derive_mac(key: &Key<Aes256Gcm>, label: &str) -> anyhow::Result<Hmac<Sha256>> {
+    let mut derive = <Hmac<Sha256> as Mac>::new_from_slice(key)
+        .map_err(|e| anyhow::anyhow!("Invalid HMAC key: {:?}", e))?;
+    derive.update(label.as_bytes());
+    let mut derived_key = derive.finalize().into_bytes();
+    let mac = <Hmac<Sha256> as Mac>::new_from_slice(&derived_key)
+        .map_err(|e| anyhow::anyhow!("Invalid HMAC key: {:?}", e));
+    derived_key.as_mut_slice().zeroize();
+    mac
+}
+
+// Kunci HMAC untuk tanda tangan export CSV
+fn export_mac(key: &Key<Aes256Gcm>) -> anyhow::Result<Hmac<Sha256>> {
+    derive_mac(key, "notes_app_rust export signature")
+}
+
+pub fn sign_export(data: &[u8], key: &Key<Aes256Gcm>) -> anyhow::Result<String> {
+    let mut mac = export_mac(key)?;
+    mac.update(data);
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+pub fn verify_export(data: &[u8], signature: &str, key: &Key<Aes256Gcm>) -> anyhow::Result<bool> {
+    let Ok(tag) = hex::decode(signature.trim()) else {
+        return Ok(false);
+    };
+    let mut mac = export_mac(key)?;
+    mac.update(data);
+    Ok(mac.verify_slice(&tag).is_ok())
+}
+
+// Meminta passphrase catatan rahasia, opsional dengan konfirmasi
+pub fn read_passphrase(confirm: bool) -> anyhow::Result<Option<String>> {
+    println!("Masukkan passphrase catatan rahasia: ");
+    let mut passphrase = String::new();
+    read_input(&mut passphrase)?;
+    let passphrase = passphrase.trim_end_matches(['\r', '\n']).to_string();
+    if passphrase.is_empty() {
+        println!("Passphrase tidak boleh kosong.");
+        return Ok(None);
+    }
+    if confirm {
+        println!("Ulangi passphrase: ");
+        let mut repeat = String::new();
+        read_input(&mut repeat)?;
+        let matches = repeat.trim_end_matches(['\r', '\n']) == passphrase;
+        repeat.zeroize();
+        if !matches {
+            println!("Passphrase tidak sama.");
+            return Ok(None);
+        }
+    }
+    Ok(Some(passphrase))
+}
+
+fn derive_passphrase_key(passphrase: &str, salt: &[u8]) -> Key<Aes256Gcm> {
+    let mut key = Key::<Aes256Gcm>::default();
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PASSPHRASE_ROUNDS, &mut key);
+    key
+}
+
+// Batas waktu idle dalam detik (0 berarti tidak pernah terkunci)
+static IDLE_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0);
+static STDIN_LINES: OnceLock<Mutex<Receiver<std::io::Result<String>>>> = OnceLock::new();
+
+// Error yang dikembalikan saat tidak ada input sampai batas waktu idle
+#[derive(Debug)]
+pub struct IdleTimeout;
+
+impl std::fmt::Display for IdleTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Sesi terkunci karena tidak ada aktivitas")
+    }
+}
+
+impl std::error::Error for IdleTimeout {}
+
+pub fn set_idle_timeout(secs: u64) {
+    IDLE_TIMEOUT_SECS.store(secs, Ordering::Relaxed);
+}
+
+// Stdin dibaca oleh thread terpisah agar pembacaan bisa dibatasi waktunya
+fn stdin_lines() -> &'static Mutex<Receiver<std::io::Result<String>>> {
+    STDIN_LINES.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || loop {
+            let mut line = String::new();
+            let result = std::io::stdin().read_line(&mut line).map(|_| line);
+            let eof = matches!(&result, Ok(l) if l.is_empty());
+            if tx.send(result).is_err() || eof {
+                break;
+            }
+        });
+        Mutex::new(rx)
+    })
+}
+
+// Pengganti stdin().read_line yang mengembalikan IdleTimeout jika pengguna tidak aktif
+pub fn read_input(buf: &mut String) -> anyhow::Result<usize> {
+    let secs = IDLE_TIMEOUT_SECS.load(Ordering::Relaxed);
+    let timeout = if secs == 0 { None } else { Some(Duration::from_secs(secs)) };
+    read_input_timeout(buf, timeout)
+}
+
+// Membaca input tanpa batas waktu, dipakai saat layar sedang terkunci
+pub fn read_input_blocking(buf: &mut String) -> anyhow::Result<usize> {
+    read_input_timeout(buf, None)
+}
+
+fn read_input_timeout(buf: &mut String, timeout: Option<Duration>) -> anyhow::Result<usize> {
+    let rx = stdin_lines().lock()
+        .map_err(|_| anyhow::anyhow!("Stdin reader poisoned"))?;
+    let line = match timeout {
+        Some(timeout) => match rx.recv_timeout(timeout) {
+            Ok(line) => line?,
+            Err(RecvTimeoutError::Timeout) => return Err(IdleTimeout.into()),
+            Err(RecvTimeoutError::Disconnected) => String::new(),
+        },
+        None => rx.recv().unwrap_or_else(|_| Ok(String::new()))?,
+    };
+    buf.push_str(&line);
+    Ok(line.len())
+}
+
+// Membaca isi catatan multi-baris sampai baris berisi "." saja.
+// Jika baris pertama ":editor", isi ditulis lewat $VISUAL/$EDITOR dengan `initial` sebagai isi awal.
+// None berarti tidak ada isi yang dimasukkan (langsung "." atau akhir input).
+pub fn read_body(initial: &str) -> anyhow::Result<Option<String>> {
+    let mut lines: Vec<String> = Vec::new();
+    loop {
+        let mut line = String::new();
+        if read_input(&mut line)? == 0 {
+            break;
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.trim() == BODY_TERMINATOR {
+            break;
+        }
+        if lines.is_empty() && line.trim() == EDITOR_COMMAND {
+            return edit_in_editor(initial).map(Some);
+        }
+        lines.push(line.to_string());
+    }
+    if lines.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(lines.join("\n").trim_end().to_string()))
+}
+
+fn edit_in_editor(initial: &str) -> anyhow::Result<String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+
+    // File sementara hanya bisa dibaca pemilik dan dihapus setelah editor ditutup
+    let path = env::temp_dir().join(format!("notes_app_rust_{}.txt", std::process::id()));
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let written = options.open(&path)
+        .and_then(|mut file| std::io::Write::write_all(&mut file, initial.as_bytes()))
+        .context("Failed to create temporary file for editor");
+    let status = written.and_then(|_| {
+        Command::new(program)
+            .args(parts)
+            .arg(&path)
+            .status()
+            .with_context(|| format!("Failed to start editor '{}'", editor))
+    });
+    let content = status.and_then(|status| {
+        if !status.success() {
+            anyhow::bail!("Editor exited with {}", status);
+        }
+        fs::read_to_string(&path).context("Failed to read temporary file")
+    });
+    let _ = fs::remove_file(&path);
+    Ok(content?.trim_end().to_string())
+}
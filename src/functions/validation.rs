@@ -0,0 +1,190 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use std::env;
+use std::fmt;
+use crate::functions::priorities::Priorities;
+
+const DEFAULT_TITLE_MAX: usize = 255;
+const DEFAULT_BODY_MAX: usize = 10_000;
+const TAG_MAX: usize = 50;
+const FOLDER_NAME_MAX: usize = 50;
+const CHECKLIST_ITEM_MAX: usize = 500;
+const PRIORITY_NAME_MAX: usize = 10;
+
+// Batas dan aturan isi catatan, bisa diubah lewat .env
+pub struct Limits {
+    pub title_max: usize,
+    pub body_max: usize,
+    pub allow_empty: bool,
+    pub strip_control: bool,
+}
+
+fn env_usize(name: &str, default: usize) -> usize {
+    env::var(name).ok().and_then(|v| v.trim().parse().ok()).unwrap_or(default)
+}
+
+pub fn env_bool(name: &str, default: bool) -> bool {
+    match env::var(name).map(|v| v.trim().to_lowercase()).as_deref() {
+        Ok("true") | Ok("1") | Ok("yes") | Ok("ya") => true,
+        Ok("false") | Ok("0") | Ok("no") | Ok("tidak") => false,
+        _ => default,
+    }
+}
+
+pub fn limits() -> Limits {
+    Limits {
+        title_max: env_usize("NOTE_TITLE_MAX_LENGTH", DEFAULT_TITLE_MAX),
+        body_max: env_usize("NOTE_BODY_MAX_LENGTH", DEFAULT_BODY_MAX),
+        allow_empty: env_bool("NOTE_ALLOW_EMPTY", false),
+        strip_control: env_bool("NOTE_STRIP_CONTROL", true),
+    }
+}
+
+// Kesalahan untuk satu field, misalnya "Judul: terlalu panjang (300/255 karakter)"
+#[derive(Debug)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+fn field_error(field: &'static str, message: String) -> FieldError {
+    FieldError { field, message }
+}
+
+// Karakter kontrol dibuang; baris baru dan tab hanya dipertahankan jika `multiline`
+fn strip_control(text: &str, multiline: bool) -> String {
+    text.chars()
+        .filter(|&c| !c.is_control() || (multiline && (c == '\n' || c == '\t')))
+        .collect()
+}
+
+fn check_length(field: &'static str, text: &str, max: usize) -> Result<(), FieldError> {
+    let len = text.chars().count();
+    if len > max {
+        return Err(field_error(field, format!("terlalu panjang ({}/{} karakter)", len, max)));
+    }
+    Ok(())
+}
+
+pub fn validate_title(title: &str) -> Result<String, FieldError> {
+    let limits = limits();
+    // Judul selalu satu baris, jadi baris baru ikut dibuang walau strip_control dimatikan
+    let title = if limits.strip_control {
+        strip_control(title, false)
+    } else {
+        title.replace(['\r', '\n'], " ")
+    };
+    let title = title.trim().to_string();
+    if title.is_empty() && !limits.allow_empty {
+        return Err(field_error("Judul", "tidak boleh kosong".to_string()));
+    }
+    check_length("Judul", &title, limits.title_max)?;
+    Ok(title)
+}
+
+pub fn validate_body(body: &str) -> Result<String, FieldError> {
+    let limits = limits();
+    let body = if limits.strip_control { strip_control(body, true) } else { body.to_string() };
+    let body = body.trim_end().to_string();
+    check_length("Isi", &body, limits.body_max)?;
+    Ok(body)
+}
+
+// Prioritas harus salah satu level yang diatur; nama dikembalikan dalam penulisan levelnya
+pub fn validate_priority(priority: &str, priorities: &Priorities) -> Result<String, FieldError> {
+    match priorities.position(priority) {
+        Some(index) => Ok(priorities.levels[index].name.clone()),
+        None => {
+            let names: Vec<&str> = priorities.levels.iter().map(|l| l.name.as_str()).collect();
+            Err(field_error("Prioritas", format!("'{}' bukan salah satu dari {}", priority.trim(), names.join(", "))))
+        }
+    }
+}
+
+// Nama level prioritas muat di kolom Prioritas pada tabel daftar catatan
+pub fn validate_priority_name(name: &str) -> Result<String, FieldError> {
+    let name = strip_control(name, false);
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(field_error("Prioritas", "tidak boleh kosong".to_string()));
+    }
+    if name.parse::<usize>().is_ok() {
+        return Err(field_error("Prioritas", "tidak boleh berupa angka".to_string()));
+    }
+    check_length("Prioritas", name, PRIORITY_NAME_MAX)?;
+    Ok(name.to_string())
+}
+
+// Tag satu kata tanpa koma, awalan # dibuang
+pub fn validate_tag(tag: &str) -> Result<String, FieldError> {
+    let tag = strip_control(tag, false);
+    let tag = tag.trim().trim_start_matches('#');
+    if tag.is_empty() {
+        return Err(field_error("Tag", "tidak boleh kosong".to_string()));
+    }
+    if tag.contains(char::is_whitespace) || tag.contains(',') {
+        return Err(field_error("Tag", format!("'{}' tidak boleh mengandung spasi atau koma", tag)));
+    }
+    check_length("Tag", tag, TAG_MAX)?;
+    Ok(tag.to_string())
+}
+
+pub fn validate_checklist_item(text: &str) -> Result<String, FieldError> {
+    let text = strip_control(text, false);
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(field_error("Item", "tidak boleh kosong".to_string()));
+    }
+    check_length("Item", text, CHECKLIST_ITEM_MAX)?;
+    Ok(text.to_string())
+}
+
+// Path folder seperti "kerja/klien/acme"; "/" atau kosong berarti root
+pub fn validate_folder(path: &str) -> Result<String, FieldError> {
+    let path = strip_control(path, false);
+    let mut segments = Vec::new();
+    for segment in path.split('/').map(str::trim).filter(|s| !s.is_empty()) {
+        if segment.contains(char::is_whitespace) {
+            return Err(field_error("Folder", format!("'{}' tidak boleh mengandung spasi", segment)));
+        }
+        check_length("Folder", segment, FOLDER_NAME_MAX)?;
+        segments.push(segment);
+    }
+    Ok(segments.join("/"))
+}
+
+// Tenggat "YYYY-MM-DD" atau "YYYY-MM-DD HH:MM" dalam waktu lokal; tanpa jam berarti akhir hari itu
+pub fn validate_due(input: &str) -> Result<DateTime<Utc>, FieldError> {
+    let input = input.trim();
+    let naive = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                .map(|day| day.and_time(NaiveTime::from_hms_opt(23, 59, 0).unwrap_or_default()))
+        })
+        .map_err(|_| field_error("Tenggat", format!("'{}' tidak valid, gunakan YYYY-MM-DD atau YYYY-MM-DD HH:MM", input)))?;
+    // Jam yang tidak ada karena pergantian waktu musim panas ditolak
+    Local.from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| field_error("Tenggat", format!("'{}' tidak ada di zona waktu lokal", input)))
+}
+
+// Memeriksa judul dan isi sekaligus agar semua kesalahan bisa ditampilkan bersama
+pub fn validate_note(title: &str, body: &str) -> Result<(String, String), Vec<FieldError>> {
+    match (validate_title(title), validate_body(body)) {
+        (Ok(title), Ok(body)) => Ok((title, body)),
+        (title, body) => Err(title.err().into_iter().chain(body.err()).collect()),
+    }
+}
+
+pub fn print_errors<E: fmt::Display>(errors: &[E]) {
+    println!("Catatan tidak valid:");
+    for error in errors {
+        println!("  - {}", error);
+    }
+}
@@ -1,104 +1,201 @@
 use rusqlite::{Connection, params};
 use aes_gcm::{Aes256Gcm, Key};
 use anyhow::Context;
-use chrono::{DateTime, Utc};
-use crate::functions::utils::{Note, decrypt_data};
+use crate::functions::utils::{
+    NOTE_COLUMNS, SECRET_PLACEHOLDER, decrypt_with_passphrase, format_due, note_from_row, read_input,
+    read_passphrase,
+};
 use crate::functions::edit_note::edit_note; // Hanya impor edit_note
 use crate::functions::delete_note::delete_note;
+use crate::functions::archive::{set_archived, set_pinned};
+use crate::functions::attachments::{attachments_menu, load_attachments};
+use crate::functions::checklist::{checklist_menu, load_items};
+use crate::functions::links::{LinkIndex, link_title, parse_links};
+use crate::functions::markdown;
+use crate::functions::priorities::Priorities;
+use crate::functions::revisions::{history_menu, save_revision};
+use crate::functions::tags::note_tags;
 
 pub fn view_note_by_id(conn: &Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<()> {
     println!("Masukkan ID catatan yang ingin dilihat: ");
     let mut id = String::new();
-    std::io::stdin().read_line(&mut id)?;
+    read_input(&mut id)?;
     let id: i32 = id.trim().parse().unwrap_or(0);
 
-    let mut stmt = conn.prepare("SELECT id, note, priority, createdAt, modifiedAt FROM notes WHERE id = ?1")
+    // Mengikuti tautan membuka catatan tujuan di tampilan yang sama
+    let mut next = Some(id);
+    while let Some(id) = next {
+        next = show_note(conn, key, id)?;
+    }
+    Ok(())
+}
+
+// Menampilkan detail catatan beserta menunya; mengembalikan ID tujuan jika pengguna mengikuti tautan
+fn show_note(conn: &Connection, key: &Key<Aes256Gcm>, id: i32) -> anyhow::Result<Option<i32>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM notes WHERE id = ?1", NOTE_COLUMNS))
         .context("Failed to prepare statement")?;
     let mut note_iter = stmt.query_and_then(
         params![id],
-        |row| -> anyhow::Result<Note> {
-            let encrypted_note: Vec<u8> = row.get(1)
-                .context("Failed to get note from row")?;
-            let encrypted_priority: Vec<u8> = row.get(2)
-                .context("Failed to get priority from row")?;
-            let note = decrypt_data(&encrypted_note, key)
-                .context("Failed to decrypt note")?;
-            let priority = decrypt_data(&encrypted_priority, key)
-                .context("Failed to decrypt priority")?;
-            let created_at_str: String = row.get(3)
-                .context("Failed to get createdAt from row")?;
-            let modified_at_str: Option<String> = row.get(4)
-                .context("Failed to get modifiedAt from row")?;
-            let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-                .map(|dt| dt.with_timezone(&Utc))
-                .context("Failed to parse createdAt")?;
-            let modified_at = modified_at_str.map(|s| {
-                DateTime::parse_from_rfc3339(&s)
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .context("Failed to parse modifiedAt")
-            }).transpose()?;
-            Ok(Note {
-                id: row.get(0).context("Failed to get id from row")?,
-                note,
-                priority,
-                created_at,
-                modified_at,
-            })
-        }
+        |row| note_from_row(row, key),
     ).context("Failed to query note by ID")?;
 
     if let Some(note) = note_iter.next() {
         let note = note?;
+        if note.deleted_at.is_some() {
+            println!("Catatan dengan ID {} ada di tempat sampah. Pulihkan lewat menu Tempat Sampah.", id);
+            return Ok(None);
+        }
+        let (title, body, items) = if note.secret {
+            let unlocked = match read_passphrase(false)? {
+                Some(passphrase) => match decrypt_with_passphrase(&note.title, &passphrase) {
+                    Ok(title) => {
+                        let body = if note.body.is_empty() {
+                            Some(String::new())
+                        } else {
+                            decrypt_with_passphrase(&note.body, &passphrase).ok()
+                        };
+                        let items = load_items(conn, key, note.id, Some(&passphrase))?;
+                        body.map(|body| (title, body, items))
+                    }
+                    Err(_) => {
+                        println!("Passphrase salah!");
+                        None
+                    }
+                },
+                None => None,
+            };
+            unlocked.unwrap_or_else(|| (SECRET_PLACEHOLDER.to_string(), String::new(), Vec::new()))
+        } else {
+            (note.title.clone(), note.body.clone(), load_items(conn, key, note.id, None)?)
+        };
         println!("\nDetail Catatan:");
         println!("ID         : {}", note.id);
-        println!("Catatan    : {}", note.note);
-        println!("Prioritas  : {}", note.priority);
+        println!("Judul      : {}", markdown::table_text(&title));
+        println!("Prioritas  : {}", Priorities::load(conn, key)?.label(&note.priority, 0));
+        println!("Folder     : /{}", note.folder);
+        if note.pinned {
+            println!("Status     : Disematkan");
+        } else if note.archived {
+            println!("Status     : Diarsipkan");
+        }
+        if let Some(due_at) = note.due_at {
+            println!("Tenggat    : {}", format_due(due_at));
+        }
+        let tags = note_tags(conn, key, note.id)?;
+        if !tags.is_empty() {
+            println!("Tag        : {}", tags.join(", "));
+        }
+        let attachments = load_attachments(conn, key, note.id)?;
+        if !attachments.is_empty() {
+            println!("Lampiran   : {} file", attachments.len());
+        }
         println!("Dibuat     : {}", note.created_at.format("%Y-%m-%d %H:%M:%S"));
         if let Some(modified_at) = note.modified_at {
             println!("Diperbarui : {}", modified_at.format("%Y-%m-%d %H:%M:%S"));
         }
+        if !body.is_empty() {
+            println!("\n{}", if markdown::enabled() { markdown::render(&body) } else { body.clone() });
+        }
+        if !items.is_empty() {
+            let done = items.iter().filter(|i| i.done).count();
+            println!("\nChecklist ({}/{} selesai):", done, items.len());
+            for item in &items {
+                println!("  [{}] {}", if item.done { "x" } else { " " }, item.text);
+            }
+        }
+
+        // Tautan dibaca dari teks yang sudah dibuka, jadi catatan rahasia yang terkunci tidak punya tautan keluar
+        let index = LinkIndex::load(conn, key)?;
+        let links = parse_links(&format!("{}\n{}", title, body));
+        if !links.is_empty() {
+            println!("\nTautan:");
+            for (number, link) in links.iter().enumerate() {
+                match index.resolve(link) {
+                    Some(target) => println!("  {}. [[{}]] -> #{} {}", number + 1, link, target.id, link_title(target)),
+                    None => println!("  {}. [[{}]] (rusak, catatan tidak ditemukan)", number + 1, link),
+                }
+            }
+        }
+        let backlinks = index.backlinks(note.id);
+        if !backlinks.is_empty() {
+            println!("\nDitautkan dari:");
+            for source in backlinks {
+                println!("  #{} {}", source.id, link_title(source));
+            }
+        }
         println!("\nMenu:");
         println!("1. Edit Catatan");
         println!("2. Hapus Catatan");
         println!("3. Ganti Prioritas");
-        println!("4. Kembali ke Menu Utama");
-        println!("Pilih opsi (1-4): ");
+        println!("4. Kelola Checklist");
+        println!("5. {}", if note.pinned { "Lepas Sematan" } else { "Sematkan Catatan" });
+        println!("6. {}", if note.archived { "Pulihkan dari Arsip" } else { "Arsipkan Catatan" });
+        println!("7. Riwayat Revisi");
+        println!("8. Ikuti Tautan");
+        println!("9. Lampiran");
+        println!("10. Kembali ke Menu Utama");
+        println!("Pilih opsi (1-10): ");
 
         let mut choice = String::new();
-        std::io::stdin().read_line(&mut choice)?;
+        read_input(&mut choice)?;
         let choice: i32 = choice.trim().parse().unwrap_or(0);
 
         match choice {
             1 => edit_note(conn, key, Some(note.id))?,
-            2 => delete_note(conn)?,
+            2 => delete_note(conn, key, Some(note.id))?,
             3 => change_priority(conn, key, note.id)?,
-            4 => println!("Kembali ke menu utama."),
+            4 => checklist_menu(conn, key, note.id)?,
+            5 => set_pinned(conn, note.id, !note.pinned)?,
+            6 => {
+                set_archived(conn, note.id, !note.archived)?;
+                if note.archived {
+                    println!("Catatan dengan ID {} dipulihkan dari arsip.", note.id);
+                } else {
+                    println!("Catatan dengan ID {} diarsipkan.", note.id);
+                }
+            }
+            7 => history_menu(conn, key, note.id)?,
+            8 => {
+                if links.is_empty() {
+                    println!("Catatan ini tidak punya tautan.");
+                    return Ok(None);
+                }
+                println!("Nomor tautan yang akan dibuka: ");
+                let mut number = String::new();
+                read_input(&mut number)?;
+                let target = number.trim().parse::<usize>().ok()
+                    .and_then(|n| links.get(n.wrapping_sub(1)))
+                    .map(|link| index.resolve(link));
+                match target {
+                    Some(Some(target)) => return Ok(Some(target.id)),
+                    Some(None) => println!("Tautan rusak, catatan tujuan tidak ditemukan."),
+                    None => println!("Nomor tautan tidak valid!"),
+                }
+            }
+            9 => attachments_menu(conn, key, note.id)?,
+            10 => println!("Kembali ke menu utama."),
             _ => println!("Pilihan tidak valid!"),
         }
     } else {
         println!("Catatan dengan ID {} tidak ditemukan!", id);
     }
-    Ok(())
+    Ok(None)
 }
 
 pub fn change_priority(conn: &Connection, key: &Key<Aes256Gcm>, id: i32) -> anyhow::Result<()> {
-    println!("Masukkan prioritas baru (1: Tinggi, 2: Sedang, 3: Rendah): ");
+    let priorities = Priorities::load(conn, key)?;
+    println!("Masukkan prioritas baru ({}): ", priorities.options());
     let mut prio_choice = String::new();
-    std::io::stdin().read_line(&mut prio_choice)?;
-    let prio_choice: i32 = prio_choice.trim().parse().unwrap_or(0);
+    read_input(&mut prio_choice)?;
 
-    let priority = match prio_choice {
-        1 => "Tinggi",
-        2 => "Sedang",
-        3 => "Rendah",
-        _ => {
-            println!("Pilihan tidak valid, prioritas tidak diubah.");
-            return Ok(());
-        }
+    let Some(priority) = priorities.find(&prio_choice) else {
+        println!("Pilihan tidak valid, prioritas tidak diubah.");
+        return Ok(());
     };
 
     let encrypted_priority = crate::functions::utils::encrypt_data(priority, key)?;
     let modified_at = chrono::Utc::now().to_rfc3339(); // Gunakan RFC 3339
+    save_revision(conn, id)?;
     conn.execute(
         "UPDATE notes SET priority = ?1, modifiedAt = ?2 WHERE id = ?3",
         params![encrypted_priority, modified_at, id],
@@ -1,84 +1,186 @@
 use anyhow::Context;
+use aes_gcm::{Aes256Gcm, Key};
 use dotenv::dotenv;
+use rusqlite::Connection;
 use std::env;
+use zeroize::Zeroize;
 
 // Deklarasi modul
 mod functions {
     pub mod add_note;
+    pub mod agenda;
+    pub mod archive;
+    pub mod attachments;
+    pub mod blind_index;
+    pub mod checklist;
+    pub mod cli;
     pub mod delete_note;
     pub mod edit_note;
     pub mod export_to_csv;
+    pub mod folders;
+    pub mod fuzzy;
+    pub mod highlight;
     pub mod import_from_csv;
     pub mod init_db;
+    pub mod links;
+    pub mod markdown;
+    pub mod priorities;
+    pub mod query_filter;
     pub mod refresh_data;
+    pub mod revisions;
+    pub mod saved_searches;
+    pub mod search_engine;
     pub mod search_notes;
+    pub mod session;
     pub mod show_notes;
+    pub mod stemmer;
+    pub mod tags;
+    pub mod trash;
     pub mod utils;
+    pub mod validation;
     pub mod view_note_by_id;
     pub mod show_all_notes;
 }
 
 // Impor fungsi dari modul
 use functions::add_note::add_note;
+use functions::agenda::show_agenda;
+use functions::archive::archive_menu;
+use functions::blind_index::ensure_index;
+use functions::cli::run_cli;
 use functions::delete_note::delete_note;
 use functions::edit_note::edit_note;
 use functions::export_to_csv::export_to_csv;
+use functions::folders::folders_menu;
 use functions::import_from_csv::import_from_csv;
 use functions::init_db::init_db;
+use functions::links::show_broken_links;
 use functions::refresh_data::refresh_data;
+use functions::saved_searches::saved_searches_menu;
 use functions::search_notes::search_notes;
 use functions::show_notes::show_notes;
 use functions::view_note_by_id::view_note_by_id;
-use functions::show_all_notes::show_all_notes;
+use functions::show_all_notes::{show_all_notes, show_filtered_notes};
+use functions::session::{lock_session, unlock_session, verify_key};
+use functions::tags::tags_menu;
+use functions::trash::{purge_expired, trash_menu};
+use functions::priorities::priorities_menu;
+use functions::utils::{IdleTimeout, read_input, set_idle_timeout};
 
 fn main() -> anyhow::Result<()> {
     dotenv().ok();
-    let encryption_key = env::var("ENCRYPTION_KEY")
+    let mut encryption_key = env::var("ENCRYPTION_KEY")
         .context("ENCRYPTION_KEY harus diset di file .env")?;
     if encryption_key.len() != 32 {
         anyhow::bail!("ENCRYPTION_KEY harus tepat 32 byte untuk AES-256-GCM");
     }
-    let key = aes_gcm::Key::<aes_gcm::Aes256Gcm>::from_slice(encryption_key.as_bytes());
+    let initial_key = *Key::<Aes256Gcm>::from_slice(encryption_key.as_bytes());
+    encryption_key.zeroize();
+    // SAFETY: belum ada thread lain yang berjalan saat variabel lingkungan dihapus
+    unsafe { env::remove_var("ENCRYPTION_KEY") };
 
-    let mut conn = init_db()?;
+    let idle_timeout = env::var("IDLE_TIMEOUT")
+        .unwrap_or_else(|_| "300".to_string())
+        .parse::<u64>()
+        .unwrap_or(300);
+    set_idle_timeout(idle_timeout);
 
-    loop {
-        show_notes(&conn, key)?;
-        println!("\nSimple Notes App");
-        println!("1. Tambah Catatan");
-        println!("2. Tampilkan Catatan (Dengan Limit)");
-        println!("3. Hapus Catatan");
-        println!("4. Edit Catatan");
-        println!("5. Refresh Data");
-        println!("6. Lihat Catatan Berdasarkan ID");
-        println!("7. Export ke CSV");
-        println!("8. Import dari CSV");
-        println!("9. Search Catatan");
-        println!("10. Tampilkan Semua Catatan"); // Tambahkan ini
-        println!("0. Keluar"); // Perbarui nomor opsi
-        println!("Pilih opsi (0-10): ");
+    let mut conn = init_db()?;
+    if !verify_key(&conn, &initial_key)? {
+        anyhow::bail!("ENCRYPTION_KEY tidak cocok dengan database");
+    }
+    ensure_index(&conn, &initial_key)?;
+    purge_expired(&mut conn)?;
 
-        let mut choice = String::new();
-        std::io::stdin().read_line(&mut choice)?;
-        let choice: i32 = choice.trim().parse().unwrap_or(0);
+    // Jika ada argumen, jalankan sebagai perintah sekali jalan tanpa menu
+    let args: Vec<String> = env::args().skip(1).collect();
+    if !args.is_empty() {
+        let mut cli_key = initial_key;
+        let result = run_cli(&mut conn, &cli_key, &args);
+        cli_key.as_mut_slice().zeroize();
+        return result;
+    }
+    let mut key = Some(initial_key);
 
-        match choice {
-            1 => add_note(&conn, key)?,
-            2 => show_notes(&conn, key)?,
-            3 => delete_note(&conn)?,
-            4 => edit_note(&conn, key, None)?,
-            5 => refresh_data(&conn, key)?,
-            6 => view_note_by_id(&conn, key)?,
-            7 => export_to_csv(&conn, key)?,
-            8 => import_from_csv(&mut conn, key)?,
-            9 => search_notes(&conn, key)?,
-            10 => show_all_notes(&conn, key)?, // Tambahkan ini
-            0 => {
+    loop {
+        let result = match &key {
+            Some(k) => run_menu(&mut conn, k),
+            None => match unlock_session(&conn)? {
+                Some(k) => {
+                    key = Some(k);
+                    continue;
+                }
+                None => Ok(false),
+            },
+        };
+        match result {
+            Ok(true) => {}
+            Ok(false) => {
                 println!("Keluar dari aplikasi.");
                 break;
             }
-            _ => println!("Pilihan tidak valid!"),
+            Err(e) if e.is::<IdleTimeout>() => lock_session(&mut key),
+            Err(e) => return Err(e),
         }
     }
+    if let Some(mut k) = key.take() {
+        k.as_mut_slice().zeroize();
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+// Satu putaran menu utama, mengembalikan false jika pengguna memilih keluar
+fn run_menu(conn: &mut Connection, key: &Key<Aes256Gcm>) -> anyhow::Result<bool> {
+    show_notes(conn, key)?;
+    println!("\nSimple Notes App");
+    println!("1. Tambah Catatan");
+    println!("2. Tampilkan Catatan (Dengan Limit)");
+    println!("3. Hapus Catatan");
+    println!("4. Edit Catatan");
+    println!("5. Refresh Data");
+    println!("6. Lihat Catatan Berdasarkan ID");
+    println!("7. Export ke CSV");
+    println!("8. Import dari CSV");
+    println!("9. Search Catatan");
+    println!("10. Tampilkan Semua Catatan"); // Tambahkan ini
+    println!("11. Tampilkan Catatan dengan Filter");
+    println!("12. Pencarian Tersimpan");
+    println!("13. Kelola Tag");
+    println!("14. Folder");
+    println!("15. Agenda");
+    println!("16. Arsip");
+    println!("17. Tempat Sampah");
+    println!("18. Periksa Tautan Rusak");
+    println!("19. Level Prioritas");
+    println!("0. Keluar"); // Perbarui nomor opsi
+    println!("Pilih opsi (0-19): ");
+
+    let mut choice = String::new();
+    read_input(&mut choice)?;
+    let choice: i32 = choice.trim().parse().unwrap_or(0);
+
+    match choice {
+        1 => add_note(conn, key)?,
+        2 => show_notes(conn, key)?,
+        3 => delete_note(conn, key, None)?,
+        4 => edit_note(conn, key, None)?,
+        5 => refresh_data(conn, key)?,
+        6 => view_note_by_id(conn, key)?,
+        7 => export_to_csv(conn, key)?,
+        8 => import_from_csv(conn, key)?,
+        9 => search_notes(conn, key)?,
+        10 => show_all_notes(conn, key)?, // Tambahkan ini
+        11 => show_filtered_notes(conn, key)?,
+        12 => saved_searches_menu(conn, key)?,
+        13 => tags_menu(conn, key)?,
+        14 => folders_menu(conn, key)?,
+        15 => show_agenda(conn, key)?,
+        16 => archive_menu(conn, key)?,
+        17 => trash_menu(conn, key)?,
+        18 => show_broken_links(conn, key)?,
+        19 => priorities_menu(conn, key)?,
+        0 => return Ok(false),
+        _ => println!("Pilihan tidak valid!"),
+    }
+    Ok(true)
+}